@@ -0,0 +1,150 @@
+//! `--als-scale <min>:<max>`: scale the LED's "on" brightness by ambient
+//! light, for dimmable hardware where a fixed brightness is either too
+//! dim to notice in a bright room or too glaring in a dark one. Reads an
+//! ambient-light sensor exposed via the kernel's IIO subsystem under
+//! `/sys/bus/iio/devices/`, the same plain-sysfs approach `led.rs` already
+//! takes for the LED itself rather than linking `libiio`.
+//!
+//! Owns writing the LED outright while active, the same "takes over from
+//! the per-node handler, tracks `camera_states` itself" shape `pulse.rs`
+//! uses for `--pulse`; see `monitor()`'s `write_issued` gate.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::rc::Rc;
+use std::time::Duration;
+
+use pipewire::loop_::LoopRef;
+
+use crate::rules::CameraState;
+use crate::writer::LedWriter;
+
+/// How often to re-read the ALS and re-apply the scaled brightness while a
+/// tracked camera is active. Ambient light changes slowly enough that
+/// `pulse.rs`'s 10ms breathing-curve tick rate would just be wasted sysfs
+/// reads here.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A rough "fully bright room" reference used to normalize a raw lux
+/// reading into the `0.0..=1.0` fraction [`start`] scales `min..=max`
+/// by. Not a calibrated photometric threshold - there's no per-panel
+/// calibration data to draw one from, just a reasonable middle-of-the-road
+/// value (typical well-lit office lighting) that errs toward reaching
+/// `max` brightness before actual outdoor-daylight lux levels.
+const REFERENCE_LUX: f64 = 1000.0;
+
+/// `min..=max` brightness bounds for `--als-scale`.
+#[derive(Debug, Clone, Copy)]
+pub struct AlsScale {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Parse a `--als-scale` value: `<min>:<max>`, both absolute brightness
+/// values (not percentages), same units as `--state-brightness`/
+/// `--standby-brightness`.
+pub fn parse_scale(spec: &str) -> Result<AlsScale, String> {
+    let (min, max) = spec
+        .split_once(':')
+        .ok_or_else(|| format!("--als-scale: expected <min>:<max>, got {:?}", spec))?;
+    let min = min
+        .parse::<u32>()
+        .map_err(|_| format!("--als-scale: invalid min {:?}", min))?;
+    let max = max
+        .parse::<u32>()
+        .map_err(|_| format!("--als-scale: invalid max {:?}", max))?;
+    if min > max {
+        return Err(format!("--als-scale: min ({}) is greater than max ({})", min, max));
+    }
+    Ok(AlsScale { min, max })
+}
+
+/// Read the first ambient-light sensor's current reading, in lux, off
+/// `/sys/bus/iio/devices/iio:device*`. Prefers `in_illuminance_input`
+/// (already scaled to lux by the driver); falls back to
+/// `in_illuminance_raw` times `in_illuminance_scale` (default scale `1.0`
+/// if that file doesn't exist) for drivers that only expose the raw ADC
+/// reading. Returns an error if no IIO device on the system exposes
+/// illuminance at all.
+fn read_lux() -> anyhow::Result<f64> {
+    let entries = fs::read_dir("/sys/bus/iio/devices")?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("iio:device"))
+        {
+            continue;
+        }
+        if let Ok(lux) = fs::read_to_string(path.join("in_illuminance_input")) {
+            if let Ok(lux) = lux.trim().parse::<f64>() {
+                return Ok(lux);
+            }
+        }
+        if let Ok(raw) = fs::read_to_string(path.join("in_illuminance_raw")) {
+            if let Ok(raw) = raw.trim().parse::<f64>() {
+                let scale = fs::read_to_string(path.join("in_illuminance_scale"))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<f64>().ok())
+                    .unwrap_or(1.0);
+                return Ok(raw * scale);
+            }
+        }
+    }
+    anyhow::bail!("no IIO ambient-light sensor found under /sys/bus/iio/devices")
+}
+
+/// Write `scale.min..=scale.max`, proportional to the most recent ALS
+/// reading, while any tracked camera is active; write brightness `0` as
+/// soon as none is. A failed ALS read logs a warning (at most once per
+/// consecutive failure, same "only warn on the edge" shape [`start`]'s
+/// own on/off tracking uses) and falls back to `scale.max`, so a sensor
+/// that goes missing mid-run degrades to "always fully bright" rather
+/// than leaving the LED dark.
+pub fn start(
+    loop_: &LoopRef,
+    writer: LedWriter,
+    camera_states: Rc<std::cell::RefCell<HashMap<u32, CameraState>>>,
+    scale: AlsScale,
+) {
+    let was_off = Rc::new(Cell::new(true));
+    let was_read_error = Rc::new(Cell::new(false));
+
+    let timer = loop_.add_timer(move |_expirations| {
+        let camera_running = camera_states.borrow().values().any(CameraState::is_active);
+
+        if !camera_running {
+            if !was_off.get() {
+                was_off.set(true);
+                writer.request(0);
+            }
+            return;
+        }
+        was_off.set(false);
+
+        let brightness = match read_lux() {
+            Ok(lux) => {
+                was_read_error.set(false);
+                let fraction = (lux / REFERENCE_LUX).clamp(0.0, 1.0);
+                scale.min + ((scale.max - scale.min) as f64 * fraction).round() as u32
+            }
+            Err(err) => {
+                if !was_read_error.get() {
+                    was_read_error.set(true);
+                    log::warn!(
+                        "--als-scale: failed to read ambient-light sensor, using max brightness: {:?}",
+                        err
+                    );
+                }
+                scale.max
+            }
+        };
+        writer.request(brightness);
+    });
+
+    let _ = timer.update_timer(Some(POLL_INTERVAL), Some(POLL_INTERVAL));
+    // Intentionally leaked, same as `pulse::start`'s timer.
+    std::mem::forget(timer);
+}
@@ -0,0 +1,73 @@
+use crate::writer::LedWriter;
+
+/// A secondary LED (e.g. caps-lock or a lid logo LED) that mirrors the
+/// camera LED's on/off state, with its own on/off values and optional
+/// inversion. Runs its own [`LedWriter`] so a slow/failing aux LED can
+/// never block or take down the primary one.
+pub struct AuxLed {
+    pub device_name: String,
+    on: u32,
+    off: u32,
+    invert: bool,
+    writer: LedWriter,
+}
+
+impl AuxLed {
+    pub fn new(device_name: String, on: u32, off: u32, invert: bool) -> Self {
+        let writer = LedWriter::spawn(
+            Box::new(crate::led::LogindBackend::new(device_name.clone())),
+            false,
+            false,
+            None,
+            None,
+        );
+        Self {
+            device_name,
+            on,
+            off,
+            invert,
+            writer,
+        }
+    }
+
+    /// Queue this aux LED to mirror the primary camera-LED state.
+    /// Inversion swaps which of `on`/`off` is used. Errors writing to
+    /// this aux LED are logged by the writer thread and never propagate
+    /// back — a broken aux LED shouldn't affect the primary indicator.
+    pub fn mirror(&self, primary_on: bool) {
+        let active = primary_on != self.invert;
+        self.writer.request(if active { self.on } else { self.off });
+    }
+}
+
+/// Parse a `--aux-led` spec: `<device>[:<on>[:<off>[:invert]]]`. `on`/
+/// `off` default to the usual binary LED values (1/0); `invert` is a
+/// literal trailing segment, not a value.
+pub fn parse_spec(spec: &str) -> Result<AuxLed, String> {
+    let mut parts = spec.split(':');
+    let device_name = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| format!("--aux-led: missing device name in {:?}", spec))?
+        .to_string();
+    let mut on = 1u32;
+    let mut off = 0u32;
+    let mut invert = false;
+    let mut position = 0;
+    for part in parts {
+        if part == "invert" {
+            invert = true;
+        } else if position == 0 {
+            on = part
+                .parse()
+                .map_err(|_| format!("--aux-led: invalid on value in {:?}", spec))?;
+            position += 1;
+        } else {
+            off = part
+                .parse()
+                .map_err(|_| format!("--aux-led: invalid off value in {:?}", spec))?;
+            position += 1;
+        }
+    }
+    Ok(AuxLed::new(device_name, on, off, invert))
+}
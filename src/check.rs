@@ -0,0 +1,87 @@
+use zbus::blocking::Connection;
+
+use crate::led::LedBackend;
+
+struct Check {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+}
+
+/// Run the preflight checks a packager or "it's not working" report would
+/// want: can we reach each D-Bus, does logind resolve our session, and is
+/// the LED device actually there. Prints a pass/fail checklist and
+/// returns `Ok(())` only if every check passed, so callers (e.g. `main`)
+/// can translate a failure into a non-zero exit.
+pub fn run(led_backend: &dyn LedBackend) -> anyhow::Result<()> {
+    let mut checks = Vec::new();
+
+    checks.push(match Connection::system() {
+        Ok(_) => Check { name: "system bus reachable", ok: true, detail: String::new() },
+        Err(err) => Check {
+            name: "system bus reachable",
+            ok: false,
+            detail: format!("{:?}", err),
+        },
+    });
+
+    checks.push(match Connection::session() {
+        Ok(_) => Check { name: "session bus reachable", ok: true, detail: String::new() },
+        Err(err) => Check {
+            name: "session bus reachable",
+            ok: false,
+            detail: format!("{:?}", err),
+        },
+    });
+
+    checks.push(match resolve_logind_session() {
+        Ok(()) => Check { name: "logind session resolvable", ok: true, detail: String::new() },
+        Err(err) => Check {
+            name: "logind session resolvable",
+            ok: false,
+            detail: format!("{:?}", err),
+        },
+    });
+
+    checks.push(match led_backend.max_brightness() {
+        Ok(max) => Check {
+            name: "LED device present",
+            ok: true,
+            detail: format!("max_brightness={}", max),
+        },
+        Err(err) => Check {
+            name: "LED device present",
+            ok: false,
+            detail: format!("{:?}", err),
+        },
+    });
+
+    let mut all_ok = true;
+    for check in &checks {
+        all_ok &= check.ok;
+        let status = if check.ok { "ok" } else { "FAIL" };
+        if check.detail.is_empty() {
+            println!("[{}] {}", status, check.name);
+        } else {
+            println!("[{}] {}: {}", status, check.name, check.detail);
+        }
+    }
+
+    if all_ok {
+        Ok(())
+    } else {
+        anyhow::bail!("one or more preflight checks failed")
+    }
+}
+
+fn resolve_logind_session() -> anyhow::Result<()> {
+    let connection = Connection::system()?;
+    connection.call_method(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1/session/auto",
+        Some("org.freedesktop.DBus.Properties"),
+        "Get",
+        &("org.freedesktop.login1.Session", "Id"),
+    )?;
+    Ok(())
+}
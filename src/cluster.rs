@@ -0,0 +1,106 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How long a disconnected `--cluster-peer` waits before reconnecting.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A minimal "is any peer's camera active" protocol for `--cluster-peer`/
+/// `--cluster-listen`: each side sends a bare `ACTIVE\n`/`INACTIVE\n` line
+/// whenever its own locally-driven state changes, newline-delimited so a
+/// peer can just read lines rather than parse a real framing format. No
+/// discovery, auth, or encryption - peers are listed explicitly via
+/// repeated `--cluster-peer <host:port>`, same trust model as the rest of
+/// this daemon's D-Bus calls (whatever's already reachable on the bus/
+/// network is trusted).
+pub struct Cluster {
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+    active_peer_count: Arc<AtomicU32>,
+}
+
+impl Cluster {
+    /// Whether any connected `--cluster-peer` last reported `ACTIVE`.
+    pub fn peer_active(&self) -> bool {
+        self.active_peer_count.load(Ordering::SeqCst) > 0
+    }
+
+    /// Tell every peer connected to our `--cluster-listen` socket our own
+    /// current active/inactive state. Dead connections are dropped
+    /// silently; `listener`'s accept loop will pick up reconnects.
+    pub fn broadcast(&self, active: bool) {
+        let line = if active { b"ACTIVE\n".as_slice() } else { b"INACTIVE\n".as_slice() };
+        self.clients.lock().unwrap().retain_mut(|client| client.write_all(line).is_ok());
+    }
+}
+
+/// Start the cluster: optionally accept connections on `listen_addr` (for
+/// peers to subscribe to our state via [`Cluster::broadcast`]), and
+/// connect out to each of `peer_addrs` (to feed [`Cluster::peer_active`]).
+/// Both sides run on their own threads, same rationale as `health.rs`'s
+/// socket accept loop - TCP I/O blocks, which doesn't fit the PipeWire
+/// main loop's non-blocking callback style.
+pub fn start(listen_addr: Option<&str>, peer_addrs: &[String]) -> anyhow::Result<Cluster> {
+    let clients: Arc<Mutex<Vec<TcpStream>>> = Arc::new(Mutex::new(Vec::new()));
+
+    if let Some(addr) = listen_addr {
+        let listener = TcpListener::bind(addr)
+            .map_err(|err| anyhow::anyhow!("--cluster-listen: failed to bind {}: {:?}", addr, err))?;
+        let clients = clients.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => clients.lock().unwrap().push(stream),
+                    Err(err) => log::warn!("--cluster-listen: accept failed: {:?}", err),
+                }
+            }
+        });
+    }
+
+    let active_peer_count = Arc::new(AtomicU32::new(0));
+    for addr in peer_addrs {
+        let addr = addr.clone();
+        let active_peer_count = active_peer_count.clone();
+        thread::spawn(move || loop {
+            match TcpStream::connect(&addr) {
+                Ok(stream) => {
+                    log::info!("--cluster-peer: connected to {}", addr);
+                    let mut was_active = false;
+                    for line in BufReader::new(stream).lines() {
+                        let line = match line {
+                            Ok(line) => line,
+                            Err(err) => {
+                                log::warn!("--cluster-peer: read from {} failed: {:?}", addr, err);
+                                break;
+                            }
+                        };
+                        let active = line.trim() == "ACTIVE";
+                        if active != was_active {
+                            if active {
+                                active_peer_count.fetch_add(1, Ordering::SeqCst);
+                            } else {
+                                active_peer_count.fetch_sub(1, Ordering::SeqCst);
+                            }
+                            was_active = active;
+                        }
+                    }
+                    if was_active {
+                        // The connection dropped while the peer's last
+                        // report was ACTIVE; don't let a disconnect leave
+                        // us stuck thinking it still is.
+                        active_peer_count.fetch_sub(1, Ordering::SeqCst);
+                    }
+                    log::warn!("--cluster-peer: disconnected from {}, will retry", addr);
+                }
+                Err(err) => {
+                    log::warn!("--cluster-peer: failed to connect to {}: {:?}", addr, err);
+                }
+            }
+            thread::sleep(RECONNECT_DELAY);
+        });
+    }
+
+    Ok(Cluster { clients, active_peer_count })
+}
@@ -0,0 +1,116 @@
+// Not referenced anywhere yet - see `CombinedRules`'s doc comment for why.
+#![allow(dead_code)]
+
+/// Whether a tracked device (camera or mic) has at least one active node.
+/// A deliberately narrow type (vs. reusing [`crate::rules::CameraState`])
+/// since the state table below only needs "active or not", not the
+/// richer per-node states `rules::CameraState` distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceState {
+    Active,
+    Inactive,
+}
+
+impl DeviceState {
+    pub fn from_active(active: bool) -> Self {
+        if active { DeviceState::Active } else { DeviceState::Inactive }
+    }
+}
+
+/// What a combined indicator should show for one (camera, mic)
+/// combination. Deliberately not a brightness value - how "on" actually
+/// renders (steady vs. blinking, which physical LED) is a presentation
+/// choice layered on top of this table, not part of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indicator {
+    Off,
+    On,
+    Blink,
+}
+
+/// A small state table mapping the four (camera, mic) combinations to an
+/// [`Indicator`], for a combined privacy light that reads differently
+/// when both are active vs. just one. [`CombinedRules::default`] is
+/// "independent LEDs, no combination logic" - each combination maps to
+/// `On` exactly when camera is active, ignoring mic, which is equivalent
+/// to this table not existing at all.
+///
+/// Not wired into `monitor()` yet: this daemon has no mic-tracking node
+/// matching (no `rules::matches_mic`, no mic `Args`/CLI flags) for the
+/// table to actually consume - see the request this was added for, which
+/// is explicitly framed as "once mic support lands". That's a separate,
+/// larger piece of work (a second `PropLookup` predicate, a second
+/// per-node state map parallel to `camera_states`, and a second LED or
+/// LED-sharing decision in `monitor()`'s brightness computation); this
+/// commit only adds the pure decision table the combination logic will
+/// need once that lands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombinedRules {
+    pub neither: Indicator,
+    pub camera_only: Indicator,
+    pub mic_only: Indicator,
+    pub both: Indicator,
+}
+
+impl Default for CombinedRules {
+    fn default() -> Self {
+        Self {
+            neither: Indicator::Off,
+            camera_only: Indicator::On,
+            mic_only: Indicator::Off,
+            both: Indicator::On,
+        }
+    }
+}
+
+impl CombinedRules {
+    pub fn resolve(&self, camera: DeviceState, mic: DeviceState) -> Indicator {
+        use DeviceState::*;
+        match (camera, mic) {
+            (Inactive, Inactive) => self.neither,
+            (Active, Inactive) => self.camera_only,
+            (Inactive, Active) => self.mic_only,
+            (Active, Active) => self.both,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_table_ignores_mic_and_tracks_camera_only() {
+        // `CombinedRules::default`'s doc comment: equivalent to this table
+        // not existing at all, i.e. each combination maps to `On` exactly
+        // when camera is active.
+        let rules = CombinedRules::default();
+        assert_eq!(rules.resolve(DeviceState::Inactive, DeviceState::Inactive), Indicator::Off);
+        assert_eq!(rules.resolve(DeviceState::Active, DeviceState::Inactive), Indicator::On);
+        assert_eq!(rules.resolve(DeviceState::Inactive, DeviceState::Active), Indicator::Off);
+        assert_eq!(rules.resolve(DeviceState::Active, DeviceState::Active), Indicator::On);
+    }
+
+    #[test]
+    fn resolve_dispatches_all_four_combinations_to_their_own_field() {
+        // A custom table distinguishing every combination, to check
+        // `resolve` actually reads the field named after the combination
+        // rather than e.g. always falling through to `both`/`neither`.
+        let rules = CombinedRules {
+            neither: Indicator::Off,
+            camera_only: Indicator::On,
+            mic_only: Indicator::Blink,
+            both: Indicator::On,
+        };
+        assert_eq!(rules.resolve(DeviceState::Inactive, DeviceState::Inactive), rules.neither);
+        assert_eq!(rules.resolve(DeviceState::Active, DeviceState::Inactive), rules.camera_only);
+        assert_eq!(rules.resolve(DeviceState::Inactive, DeviceState::Active), rules.mic_only);
+        assert_eq!(rules.resolve(DeviceState::Active, DeviceState::Active), rules.both);
+    }
+
+    #[test]
+    fn device_state_from_active() {
+        assert_eq!(DeviceState::from_active(true), DeviceState::Active);
+        assert_eq!(DeviceState::from_active(false), DeviceState::Inactive);
+    }
+}
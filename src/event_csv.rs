@@ -0,0 +1,78 @@
+//! Append-only CSV event log for `--event-csv`: one row per camera state
+//! change, meant to be opened directly in a spreadsheet for usage-pattern
+//! analysis or auditing. A focused, narrow output distinct from the
+//! `log::info!`/`--json` status logging elsewhere in this crate, which
+//! isn't meant to be machine-imported.
+//!
+//! Columns: `timestamp,camera_id,product,app,state,brightness`.
+//! `timestamp` is seconds since the Unix epoch (no `chrono` dependency in
+//! this crate). Rows are flushed immediately after each write, so a crash
+//! right after loses nothing already recorded.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+
+pub struct EventCsv {
+    file: File,
+}
+
+impl EventCsv {
+    /// Open (creating if needed, appending if it already exists) the CSV
+    /// at `path`, writing the header row only when the file didn't already
+    /// exist. Errors are returned rather than logged here, so the caller
+    /// can decide how to degrade (see `monitor()`: a failed open just
+    /// disables `--event-csv` for this run rather than aborting startup).
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let is_new = !Path::new(path).exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open {:?}", path))?;
+        if is_new {
+            writeln!(file, "timestamp,camera_id,product,app,state,brightness")
+                .with_context(|| format!("failed to write header to {:?}", path))?;
+            file.flush().with_context(|| format!("failed to flush {:?}", path))?;
+        }
+        Ok(Self { file })
+    }
+
+    /// Append one row for a state change and flush immediately.
+    pub fn log(&mut self, camera_id: u32, product: &str, app: &str, state: &str, brightness: u32) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if let Err(err) = writeln!(
+            self.file,
+            "{},{},{},{},{},{}",
+            timestamp,
+            camera_id,
+            escape(product),
+            escape(app),
+            state,
+            brightness
+        ) {
+            log::warn!("--event-csv: failed to write row: {:?}", err);
+            return;
+        }
+        if let Err(err) = self.file.flush() {
+            log::warn!("--event-csv: failed to flush: {:?}", err);
+        }
+    }
+}
+
+/// Quote a field if it contains a comma, quote, or newline — the only
+/// characters that would otherwise break CSV parsing. `product`/`app` come
+/// from PipeWire node props, which aren't under our control.
+fn escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
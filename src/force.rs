@@ -0,0 +1,98 @@
+//! A file-based manual LED override, for `--force-state-file`.
+//!
+//! A `ForceState`/`ClearForce` pair on a D-Bus status interface was
+//! requested, with a signal file offered as a fallback. The D-Bus side
+//! doesn't exist — there's no object-server in this tree at all yet, same
+//! prerequisite gap as the `Pause()`/`Resume()` request declined in
+//! `main.rs`'s `monitor()` — but the fallback needs nothing this tree
+//! doesn't already have: periodic polling via a PipeWire main-loop timer,
+//! and a flat text file.
+//!
+//! File format: a single line `<brightness> <seconds>`, e.g. `1 30` to
+//! force the LED to brightness 1 for 30 seconds. A missing file, or one
+//! containing exactly `clear`, clears any active override immediately.
+//! Malformed content is logged and ignored, leaving whatever override (or
+//! lack of one) was already in effect. The file is polled on [`POLL_INTERVAL`],
+//! not watched — no `inotify` dependency exists in this tree — so a change
+//! takes up to that long to take effect.
+
+use std::cell::RefCell;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// How often [`Force::poll`] should be called; `monitor()` drives this via
+/// a regular `add_timer`, same idiom as every other periodic check in that
+/// function.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tracks the override file's content and, once armed, when it expires.
+pub struct Force {
+    path: String,
+    state: RefCell<Option<(u32, Instant)>>,
+    /// The file content last acted on, so a request already in effect
+    /// isn't silently re-armed (resetting its remaining duration) on every
+    /// poll just because the file is still there.
+    last_content: RefCell<Option<String>>,
+}
+
+impl Force {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            state: RefCell::new(None),
+            last_content: RefCell::new(None),
+        }
+    }
+
+    /// Re-read the file and update internal state if its content changed
+    /// since the last poll. Call on every [`POLL_INTERVAL`] tick, and once
+    /// up front before the first [`Force::active`] check.
+    pub fn poll(&self) {
+        let content = fs::read_to_string(&self.path).ok();
+        if content == *self.last_content.borrow() {
+            return;
+        }
+        match content.as_deref().map(str::trim) {
+            None | Some("") | Some("clear") => {
+                *self.state.borrow_mut() = None;
+            }
+            Some(line) => {
+                let parsed = line.split_once(' ').and_then(|(brightness, secs)| {
+                    Some((brightness.trim().parse::<u32>().ok()?, secs.trim().parse::<u64>().ok()?))
+                });
+                match parsed {
+                    Some((brightness, secs)) => {
+                        log::info!("--force-state-file: forcing LED to {} for {}s", brightness, secs);
+                        *self.state.borrow_mut() =
+                            Some((brightness, Instant::now() + Duration::from_secs(secs)));
+                    }
+                    None => {
+                        log::warn!(
+                            "--force-state-file: malformed content {:?}, ignoring (previous override, if any, keeps applying)",
+                            line
+                        );
+                    }
+                }
+            }
+        }
+        *self.last_content.borrow_mut() = content;
+    }
+
+    /// The brightness to force, if an override is currently active.
+    /// Expiry is checked lazily here — the main loop's own timer calls
+    /// [`Force::poll`] independently of how often this is read — so an
+    /// override past its duration clears itself the next time anything
+    /// actually asks, rather than needing its own dedicated timer.
+    pub fn active(&self) -> Option<u32> {
+        let mut state = self.state.borrow_mut();
+        match *state {
+            Some((brightness, until)) if Instant::now() < until => Some(brightness),
+            Some(_) => {
+                log::info!("--force-state-file: override expired, resuming automatic control");
+                *state = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
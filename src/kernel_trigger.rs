@@ -0,0 +1,63 @@
+use anyhow::Context;
+
+/// Path to a given LED's sysfs directory, same naming [`crate::led`] uses.
+fn led_dir(device_name: &str) -> String {
+    format!("/sys/class/leds/{}", device_name)
+}
+
+/// Write `contents` to the sysfs attribute at `path`, turning a bare
+/// "Permission denied" into something actionable: these writes go
+/// straight to sysfs rather than through logind's permission-managed
+/// D-Bus calls (see [`configure_blink`]'s doc comment), so a normal unprivileged
+/// user hits EACCES here far more often than with [`crate::led::LogindBackend`].
+fn write_sysfs_attr(path: &str, contents: &str, device_name: &str) -> anyhow::Result<()> {
+    std::fs::write(path, contents).map_err(|err| {
+        if err.kind() == std::io::ErrorKind::PermissionDenied {
+            anyhow::anyhow!(
+                "permission denied writing {}: this needs either a udev rule granting access, \
+                 e.g. `SUBSYSTEM==\"leds\", KERNEL==\"{}\", MODE=\"0664\", GROUP=\"video\"` in \
+                 /etc/udev/rules.d/, or skip --use-kernel-trigger and let the default \
+                 LogindBackend drive the LED through logind instead, which doesn't need raw \
+                 sysfs write access",
+                path,
+                device_name
+            )
+        } else {
+            anyhow::Error::new(err).context(format!("failed to write {}", path))
+        }
+    })
+}
+
+/// Whether the kernel's `timer` trigger (periodic on/off blink driven by
+/// the kernel rather than a userspace timer) is listed as available for
+/// this LED. The `trigger` file lists every available trigger
+/// space-separated, with the currently-active one in `[brackets]`.
+pub fn is_available(device_name: &str) -> bool {
+    std::fs::read_to_string(format!("{}/trigger", led_dir(device_name)))
+        .map(|contents| contents.split_whitespace().any(|word| word.trim_matches(['[', ']']) == "timer"))
+        .unwrap_or(false)
+}
+
+/// Offload a periodic on/off blink to the kernel's `timer` LED trigger,
+/// so userspace doesn't need its own wakeup every tick (see `pwm.rs`).
+/// Requires write access to the LED's sysfs attributes, same as any
+/// direct (non-logind) LED write — typically root, unlike the default
+/// `LogindBackend` which goes through logind's permission-managed D-Bus
+/// call instead.
+pub fn configure_blink(device_name: &str, delay_on_ms: u64, delay_off_ms: u64) -> anyhow::Result<()> {
+    let dir = led_dir(device_name);
+    write_sysfs_attr(&format!("{}/trigger", dir), "timer", device_name)
+        .context("failed to select timer trigger")?;
+    write_sysfs_attr(&format!("{}/delay_on", dir), &delay_on_ms.to_string(), device_name)
+        .context("failed to set delay_on")?;
+    write_sysfs_attr(&format!("{}/delay_off", dir), &delay_off_ms.to_string(), device_name)
+        .context("failed to set delay_off")?;
+    Ok(())
+}
+
+/// Hand the LED back to plain brightness control, e.g. before a
+/// non-blinking write or on exit.
+pub fn clear(device_name: &str) -> anyhow::Result<()> {
+    write_sysfs_attr(&format!("{}/trigger", led_dir(device_name)), "none", device_name)
+        .context("failed to clear trigger")
+}
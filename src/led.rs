@@ -0,0 +1,643 @@
+use anyhow::Context;
+use std::cell::{Cell, RefCell};
+use std::process::Command;
+use std::sync::mpsc;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+use zbus::blocking::Connection;
+
+use crate::X13S_LED_DEVICE_NAME;
+
+/// Set by `--dbus-timeout` at startup, before any D-Bus call is made.
+/// `OnceLock` rather than a plain global so every call site (this
+/// module's `LogindBackend` and `main.rs`'s `notification()`/
+/// `close_notification()`) reads the same value without threading an
+/// `Args`/timeout parameter through each of them.
+static DBUS_TIMEOUT: OnceLock<Option<Duration>> = OnceLock::new();
+
+/// Set the timeout used by [`call_with_timeout`]. Only the first call
+/// takes effect, same caveat as any `OnceLock` - `main()` calls this
+/// once, before `monitor()`/`simulate()`/`notification()` can run.
+pub fn set_dbus_timeout(timeout: Option<Duration>) {
+    let _ = DBUS_TIMEOUT.set(timeout);
+}
+
+fn dbus_timeout() -> Option<Duration> {
+    DBUS_TIMEOUT.get().copied().flatten()
+}
+
+/// Run a blocking D-Bus call on a dedicated thread and wait at most the
+/// configured `--dbus-timeout` for it, so a hung bus can't stall the LED
+/// writer thread (or, for notifications, the PipeWire main loop) forever.
+/// With no `--dbus-timeout` set, this just runs `call` directly on the
+/// calling thread, same as before this existed. `call` must be
+/// `Send + 'static` since it may run on its own thread; on timeout, the
+/// spawned thread is abandoned (not joined) and still completes in the
+/// background, same tradeoff `writer.rs`'s worker thread and
+/// `suspend.rs`/`sessionlock.rs`'s watcher threads already make by never
+/// joining.
+pub(crate) fn call_with_timeout<T: Send + 'static>(
+    call: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<T> {
+    let Some(timeout) = dbus_timeout() else {
+        return call();
+    };
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(call());
+    });
+    rx.recv_timeout(timeout)
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("D-Bus call timed out after {:?}", timeout)))
+}
+
+/// Connect via `factory`, memoizing only a *successful* connection into
+/// `cache`. Caching a `zbus::Result` directly (as an earlier version of
+/// this did) would lock in a transient early failure (e.g. the bus not
+/// up yet at boot) forever, since `OnceLock` can only ever be
+/// initialized once; by only storing the `Connection` itself, a failed
+/// attempt leaves the lock empty so the next call retries.
+///
+/// `cache` is taken as a parameter, and `factory` is generic, purely so
+/// a test can pass its own `OnceLock` and a counting closure and assert
+/// that one only actually runs once across several calls - see the test
+/// module below. A function-local `static OnceLock` here instead (an
+/// earlier version of this did that) would *not* give each generic
+/// instantiation its own slot: a local `static`'s type doesn't depend on
+/// `F`, so every `F` sharing this function still shares the one
+/// underlying static, letting a test's cache collide with
+/// [`system_connection`]'s. [`system_connection`] is still the only real
+/// call site, wrapping this with its own `static` and
+/// `Connection::system`.
+fn system_connection_with<F: Fn() -> anyhow::Result<Connection>>(
+    cache: &OnceLock<Connection>,
+    factory: F,
+) -> anyhow::Result<Connection> {
+    if let Some(connection) = cache.get() {
+        return Ok(connection.clone());
+    }
+    let connection = factory()?;
+    // If another thread won the race, `set` fails and we just use our own
+    // handle; both are equally valid connections to the same bus.
+    let _ = cache.set(connection.clone());
+    Ok(connection)
+}
+
+/// Connect to the system bus, memoizing the connection - see
+/// [`system_connection_with`].
+///
+/// This always dials the real system bus — there's no way to point it at
+/// a private/mock bus for a test, which is also true of `notification()`
+/// and `close_notification()` in `main.rs` (session bus) and
+/// `check::resolve_logind_session`. An in-process mock D-Bus service (a
+/// private `zbus::connection::Builder` bus with recording `SetBrightness`/
+/// `Notify` handlers) would need all of these call sites to take an
+/// injected `Connection`/address rather than calling `Connection::system`/
+/// `Connection::session` directly; that's a wider refactor than adding
+/// the mock harness itself, and hasn't been done.
+fn system_connection() -> anyhow::Result<Connection> {
+    static CONNECTION: OnceLock<Connection> = OnceLock::new();
+    system_connection_with(&CONNECTION, || {
+        Connection::system().context("error connecting to system bus")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use std::thread;
+    use zbus::blocking::connection::Builder;
+    use zbus::Guid;
+
+    /// A private peer-to-peer `Connection` over an in-process
+    /// `UnixStream` pair, rather than `Connection::system`/
+    /// `Connection::session` - a real, working `Connection` that needs
+    /// no D-Bus daemon, bus address, or environment
+    /// (`DBUS_SESSION_BUS_ADDRESS` etc.) to exist, so these tests stay
+    /// hermetic. `zbus`'s "p2p" feature (a `[dev-dependencies]`-only
+    /// entry in `Cargo.toml`, so it never reaches the real binary) gates
+    /// the `.server()`/`.p2p()` builder methods this needs. Each side's
+    /// handshake blocks until the other side responds, so the server
+    /// side is built on its own thread while the client side builds on
+    /// the caller's.
+    fn p2p_connection() -> Connection {
+        let guid = Guid::generate();
+        let (server_end, client_end) = UnixStream::pair().expect("unix socketpair");
+        let server = thread::spawn(move || {
+            Builder::unix_stream(server_end)
+                .server(guid)
+                .expect("valid guid")
+                .p2p()
+                .build()
+                .expect("server side of the p2p pair")
+        });
+        let client = Builder::unix_stream(client_end)
+            .p2p()
+            .build()
+            .expect("client side of the p2p pair");
+        server.join().expect("server thread panicked");
+        client
+    }
+
+    #[test]
+    fn system_connection_with_memoizes_the_factory_across_calls() {
+        let cache = OnceLock::new();
+        let calls = Cell::new(0u32);
+        let connect = || {
+            calls.set(calls.get() + 1);
+            Ok(p2p_connection())
+        };
+        let first = system_connection_with(&cache, &connect);
+        let second = system_connection_with(&cache, &connect);
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn separate_caches_stay_independent() {
+        // This is exactly the bug an earlier version of
+        // `system_connection_with` had: a function-local `static`
+        // inside the generic function, instead of a cache taken as a
+        // parameter, would have given every `F` the same slot - so
+        // populating one cache would make it look like this one was
+        // already populated too.
+        let cache_a = OnceLock::new();
+        let cache_b: OnceLock<Connection> = OnceLock::new();
+        let _ = system_connection_with(&cache_a, || Ok(p2p_connection()));
+        assert!(cache_a.get().is_some());
+        assert!(cache_b.get().is_none());
+    }
+}
+
+/// Abstraction over however a given LED is actually driven, so callers
+/// don't need to care whether writes go through logind, sysfs, or
+/// something else. `Send` because backends are owned and run on a
+/// dedicated writer thread (see `writer.rs`) rather than the PipeWire
+/// main loop, so neither a slow D-Bus call nor a slow sysfs write ever
+/// blocks event processing.
+pub trait LedBackend: Send {
+    /// Write a brightness value to the LED. Implementations should not
+    /// silently clamp out-of-range values; callers validate against
+    /// [`LedBackend::max_brightness`] first.
+    fn set_brightness(&self, brightness: u32) -> anyhow::Result<()>;
+
+    /// The maximum brightness value this LED accepts, read from sysfs
+    /// regardless of which mechanism is used to write.
+    fn max_brightness(&self) -> anyhow::Result<u32>;
+
+    /// The sysfs LED device name this backend writes to, for
+    /// `--verify-write`'s readback check — the sysfs-backed backends,
+    /// logind-mediated ones included, write to a named LED under
+    /// `/sys/class/leds/`, since that's what `max_brightness` already reads
+    /// from above. [`CommandBackend`], `DummyBackend` and `GpioBackend` are
+    /// the exceptions: they have no sysfs device of their own, so
+    /// `--verify-write` against them just fails the readback
+    /// (logged as a warning, same as any other unreadable path) rather
+    /// than verifying anything. Returns an owned `String` rather than
+    /// `&str` since [`LogindBackend`] can re-resolve to a different
+    /// device name at runtime (see [`LogindBackend::reresolve`]) and
+    /// can't borrow out of the `RefCell` that makes that possible.
+    fn device_name(&self) -> String;
+}
+
+/// Writes brightness via `org.freedesktop.login1`'s `SetBrightness`, which
+/// is what lets an unprivileged user toggle an LED under sysfs
+/// permissions managed by logind.
+pub struct LogindBackend {
+    /// All acceptable device names, in priority order, for
+    /// [`LogindBackend::reresolve`] to fall back through if `device_name`
+    /// stops responding. Just `[device_name]` when constructed via
+    /// [`LogindBackend::new`]/[`LogindBackend::with_percentage`]; callers
+    /// that already have a `--led-device` candidate list (`monitor()`,
+    /// `simulate()`) should use [`LogindBackend::with_candidates`] to
+    /// give re-resolution somewhere to actually fall back to.
+    candidates: Vec<String>,
+    /// The device name currently in use. In a `RefCell` (not `Cell`,
+    /// since `String` isn't `Copy`) so `reresolve` can update it from
+    /// `&self`, matching `supports_percentage`'s existing interior-
+    /// mutability shape for the same `&self`-only trait reason.
+    device_name: RefCell<String>,
+    /// Prefer a percentage-based `SetBrightnessPercentage` call over the
+    /// absolute `SetBrightness` one, on logind versions that expose it.
+    /// Existence is checked via introspection before use and cached, so
+    /// this is a no-op (falls back to `SetBrightness`) on logind versions
+    /// that don't have it.
+    use_percentage: bool,
+    supports_percentage: Cell<Option<bool>>,
+}
+
+impl LogindBackend {
+    pub fn new(device_name: impl Into<String>) -> Self {
+        let device_name = device_name.into();
+        Self {
+            candidates: vec![device_name.clone()],
+            device_name: RefCell::new(device_name),
+            use_percentage: false,
+            supports_percentage: Cell::new(None),
+        }
+    }
+
+    pub fn with_percentage(device_name: impl Into<String>) -> Self {
+        let device_name = device_name.into();
+        Self {
+            candidates: vec![device_name.clone()],
+            device_name: RefCell::new(device_name),
+            use_percentage: true,
+            supports_percentage: Cell::new(None),
+        }
+    }
+
+    /// Like [`LogindBackend::new`]/[`LogindBackend::with_percentage`],
+    /// but with the full `--led-device` candidate list so a write failure
+    /// (e.g. udev renamed the device across suspend/resume or a kernel
+    /// module reload) can re-resolve to whichever candidate responds now,
+    /// not just retry the same name.
+    pub fn with_candidates(candidates: Vec<String>, use_percentage: bool) -> Self {
+        let device_name = candidates.first().cloned().unwrap_or_default();
+        Self {
+            candidates,
+            device_name: RefCell::new(device_name),
+            use_percentage,
+            supports_percentage: Cell::new(None),
+        }
+    }
+
+    /// Re-run [`select_device`] over `candidates` and, if a responding
+    /// device is found that differs from the one currently in use,
+    /// switch to it. Called after a write or `max_brightness` read fails,
+    /// so a transient udev rename doesn't leave this backend permanently
+    /// stuck writing to a device name that no longer exists.
+    fn reresolve(&self) -> Option<String> {
+        let current = self.device_name.borrow().clone();
+        let resolved = select_device(&self.candidates)?;
+        if resolved == current {
+            return None;
+        }
+        log::info!("LED device {:?} stopped responding, re-resolved to {:?}", current, resolved);
+        *self.device_name.borrow_mut() = resolved.clone();
+        Some(resolved)
+    }
+
+    /// Whether `SetBrightnessPercentage` is listed on the session's
+    /// `org.freedesktop.login1.Session` interface, checked once via
+    /// `org.freedesktop.DBus.Introspectable` and cached for the life of
+    /// this backend.
+    fn supports_percentage(&self) -> anyhow::Result<bool> {
+        if let Some(supported) = self.supports_percentage.get() {
+            return Ok(supported);
+        }
+        let connection = system_connection()?;
+        let xml: String = call_with_timeout(move || {
+            let reply = connection.call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1/session/auto",
+                Some("org.freedesktop.DBus.Introspectable"),
+                "Introspect",
+                &(),
+            )?;
+            Ok(reply.body().deserialize()?)
+        })?;
+        let supported = xml.contains("SetBrightnessPercentage");
+        self.supports_percentage.set(Some(supported));
+        Ok(supported)
+    }
+}
+
+impl LogindBackend {
+    fn write_brightness(&self, brightness: u32) -> anyhow::Result<()> {
+        let connection = system_connection()?;
+        let device_name = self.device_name.borrow().clone();
+
+        if self.use_percentage && self.supports_percentage()? {
+            let max = self.read_max_brightness()?;
+            let percentage = if max == 0 { 0 } else { brightness * 100 / max };
+            return call_with_timeout(move || {
+                let _m = connection.call_method(
+                    Some("org.freedesktop.login1"),
+                    "/org/freedesktop/login1/session/auto",
+                    Some("org.freedesktop.login1.Session"),
+                    "SetBrightnessPercentage",
+                    &("leds", device_name, percentage),
+                )?;
+                Ok(())
+            });
+        }
+
+        call_with_timeout(move || {
+            let _m = connection.call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1/session/auto",
+                Some("org.freedesktop.login1.Session"),
+                "SetBrightness",
+                &("leds", device_name, brightness),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn read_max_brightness(&self) -> anyhow::Result<u32> {
+        let device_name = self.device_name.borrow().clone();
+        let path = format!("/sys/class/leds/{}/max_brightness", device_name);
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path))?;
+        contents
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid max_brightness in {}", path))
+    }
+}
+
+impl LedBackend for LogindBackend {
+    fn set_brightness(&self, brightness: u32) -> anyhow::Result<()> {
+        match self.write_brightness(brightness) {
+            Ok(()) => Ok(()),
+            Err(_err) if self.reresolve().is_some() => self.write_brightness(brightness),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn max_brightness(&self) -> anyhow::Result<u32> {
+        match self.read_max_brightness() {
+            Ok(max) => Ok(max),
+            Err(_err) if self.reresolve().is_some() => self.read_max_brightness(),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn device_name(&self) -> String {
+        self.device_name.borrow().clone()
+    }
+}
+
+/// Delegates LED control to an external command, substituting `{brightness}`
+/// with the value to write, for hardware exotic enough that integrating it
+/// directly isn't worth patching this crate for. The command is run
+/// through `sh -c` so `{brightness}` can appear anywhere in a shell
+/// pipeline/argument list, not just as a bare first argument.
+pub struct CommandBackend {
+    /// e.g. `"/usr/local/bin/setled {brightness}"`.
+    command_template: String,
+    /// No sysfs device backs this, so there's nothing to read
+    /// `max_brightness` from; the caller supplies it (see `--led-command-max`),
+    /// defaulting to `1` (on/off only) same as an unconfigured binary LED.
+    max_brightness: u32,
+}
+
+impl CommandBackend {
+    pub fn new(command_template: impl Into<String>, max_brightness: u32) -> Self {
+        Self {
+            command_template: command_template.into(),
+            max_brightness,
+        }
+    }
+}
+
+impl LedBackend for CommandBackend {
+    fn set_brightness(&self, brightness: u32) -> anyhow::Result<()> {
+        let command = self.command_template.replace("{brightness}", &brightness.to_string());
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .status()
+            .with_context(|| format!("failed to spawn LED command {:?}", command))?;
+        if !status.success() {
+            anyhow::bail!("LED command {:?} exited with {:?}", command, status.code());
+        }
+        Ok(())
+    }
+
+    fn max_brightness(&self) -> anyhow::Result<u32> {
+        Ok(self.max_brightness)
+    }
+
+    fn device_name(&self) -> String {
+        "command".to_string()
+    }
+}
+
+/// An in-memory backend that performs no real I/O, for exercising
+/// backend-agnostic logic (`parse_args`, `rules`, the decision code in
+/// `monitor()`) on platforms without a real LED device or sysfs tree —
+/// see the `dummy` feature's doc comment in `Cargo.toml` for what this
+/// does and doesn't cover. Every write is logged at debug and recorded
+/// so a caller can assert on the last value set.
+#[cfg(feature = "dummy")]
+pub struct DummyBackend {
+    brightness: Cell<u32>,
+    max_brightness: u32,
+}
+
+#[cfg(feature = "dummy")]
+impl DummyBackend {
+    pub fn new(max_brightness: u32) -> Self {
+        Self {
+            brightness: Cell::new(0),
+            max_brightness,
+        }
+    }
+
+    /// The last value passed to `set_brightness`, for test assertions.
+    pub fn last_brightness(&self) -> u32 {
+        self.brightness.get()
+    }
+}
+
+#[cfg(feature = "dummy")]
+impl LedBackend for DummyBackend {
+    fn set_brightness(&self, brightness: u32) -> anyhow::Result<()> {
+        log::debug!("dummy backend: set_brightness({})", brightness);
+        self.brightness.set(brightness);
+        Ok(())
+    }
+
+    fn max_brightness(&self) -> anyhow::Result<u32> {
+        Ok(self.max_brightness)
+    }
+
+    fn device_name(&self) -> String {
+        "dummy".to_string()
+    }
+}
+
+/// Mirrors camera state to a GPIO line (e.g. an external privacy light
+/// wired to a dock) instead of an onboard LED, via the legacy sysfs GPIO
+/// interface (`/sys/class/gpio/export` plus `/sys/class/gpio/gpioN/
+/// {direction,value}`), not the newer `/dev/gpiochipN` character-device
+/// ioctl interface a `gpiod`-style crate would use — this crate doesn't
+/// depend on one, and adding it just for an opt-in feature didn't seem
+/// worth it. `chip` is a sysfs gpiochip path (e.g.
+/// `/sys/class/gpio/gpiochip0`); `line` is the offset within it, added
+/// to the chip's `base` to get the global GPIO number sysfs expects.
+#[cfg(feature = "gpio")]
+pub struct GpioBackend {
+    gpio_number: u32,
+}
+
+#[cfg(feature = "gpio")]
+impl GpioBackend {
+    /// Resolve `chip`+`line` to a global GPIO number and export it if
+    /// it isn't already, same one-time setup `is_controllable`/
+    /// `select_device` skip by reading sysfs directly rather than
+    /// shelling out to a `gpio-utils`-style tool.
+    pub fn new(chip: &str, line: u32) -> anyhow::Result<Self> {
+        let base: u32 = std::fs::read_to_string(format!("{}/base", chip))
+            .with_context(|| format!("failed to read {}/base", chip))?
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid base in {}/base", chip))?;
+        let gpio_number = base + line;
+
+        let gpio_path = format!("/sys/class/gpio/gpio{}", gpio_number);
+        if !std::path::Path::new(&gpio_path).exists() {
+            std::fs::write("/sys/class/gpio/export", gpio_number.to_string())
+                .with_context(|| format!("failed to export gpio{}", gpio_number))?;
+        }
+        std::fs::write(format!("{}/direction", gpio_path), "out")
+            .with_context(|| format!("failed to set gpio{} direction to out", gpio_number))?;
+
+        Ok(Self { gpio_number })
+    }
+}
+
+#[cfg(feature = "gpio")]
+impl LedBackend for GpioBackend {
+    fn set_brightness(&self, brightness: u32) -> anyhow::Result<()> {
+        let value = if brightness > 0 { "1" } else { "0" };
+        std::fs::write(
+            format!("/sys/class/gpio/gpio{}/value", self.gpio_number),
+            value,
+        )
+        .with_context(|| format!("failed to write gpio{} value", self.gpio_number))
+    }
+
+    fn max_brightness(&self) -> anyhow::Result<u32> {
+        // A GPIO line is on/off only, same as an unconfigured
+        // `CommandBackend`.
+        Ok(1)
+    }
+
+    fn device_name(&self) -> String {
+        "gpio".to_string()
+    }
+}
+
+/// Cascades a write attempt through an ordered list of backends, trying
+/// each in turn until one succeeds, for `--fallback-led-device` — e.g.
+/// falling back from logind to a directly-written sysfs path (via
+/// `CommandBackend`) when a session-less context (no logind session,
+/// see `check::resolve_logind_session`) makes the primary backend
+/// unusable. Only returns an error once every backend in the list has
+/// failed, with that error being the *last* backend's (the others'
+/// failures are logged as they're skipped past, not discarded silently).
+pub struct FallbackLedBackend {
+    backends: Vec<Box<dyn LedBackend>>,
+}
+
+impl FallbackLedBackend {
+    pub fn new(backends: Vec<Box<dyn LedBackend>>) -> Self {
+        Self { backends }
+    }
+}
+
+impl LedBackend for FallbackLedBackend {
+    fn set_brightness(&self, brightness: u32) -> anyhow::Result<()> {
+        let mut last_err = None;
+        for (index, backend) in self.backends.iter().enumerate() {
+            match backend.set_brightness(brightness) {
+                Ok(()) => {
+                    if index > 0 {
+                        log::info!(
+                            "--fallback-led-device: backend #{} ({}) succeeded after {} earlier failure(s)",
+                            index,
+                            backend.device_name(),
+                            index
+                        );
+                    }
+                    return Ok(());
+                }
+                Err(err) => {
+                    log::warn!(
+                        "--fallback-led-device: backend #{} ({}) failed: {:?}",
+                        index,
+                        backend.device_name(),
+                        err
+                    );
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("--fallback-led-device: no backends configured")))
+    }
+
+    /// The primary (first) backend's `max_brightness`, same reasoning as
+    /// `device_name` below — this is what `--verify-write`/validation
+    /// should treat as ground truth, since that's the backend expected to
+    /// succeed in normal operation.
+    fn max_brightness(&self) -> anyhow::Result<u32> {
+        match self.backends.first() {
+            Some(backend) => backend.max_brightness(),
+            None => anyhow::bail!("--fallback-led-device: no backends configured"),
+        }
+    }
+
+    fn device_name(&self) -> String {
+        self.backends
+            .first()
+            .map(|backend| backend.device_name())
+            .unwrap_or_else(|| "fallback".to_string())
+    }
+}
+
+/// Read the sysfs `brightness` attribute for `device_name`, for
+/// `--verify-write`'s post-write readback check.
+pub fn read_brightness(device_name: &str) -> anyhow::Result<u32> {
+    let path = format!("/sys/class/leds/{}/brightness", device_name);
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("failed to read {}", path))?;
+    contents
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid brightness in {}", path))
+}
+
+/// Validate `brightness` against the backend's `max_brightness()` and
+/// write it if in range, logging and refusing to write otherwise. This
+/// avoids silent no-ops when a caller requests an unsupported level.
+pub fn set_brightness_checked(backend: &dyn LedBackend, brightness: u32) -> anyhow::Result<()> {
+    let max = backend.max_brightness()?;
+    if brightness > max {
+        anyhow::bail!(
+            "requested brightness {} exceeds max_brightness {}",
+            brightness,
+            max
+        );
+    }
+    backend.set_brightness(brightness)
+}
+
+pub fn default_backend() -> LogindBackend {
+    LogindBackend::new(X13S_LED_DEVICE_NAME)
+}
+
+pub fn default_backend_with_percentage() -> LogindBackend {
+    LogindBackend::with_percentage(X13S_LED_DEVICE_NAME)
+}
+
+/// Whether `device_name` looks controllable: its sysfs `max_brightness`
+/// attribute exists and parses. Same existence probe `check::run` uses
+/// for its "LED device present" check, reused here to pick among several
+/// candidate device names (see `--led-device`) without needing a live
+/// D-Bus round-trip just to find out a name doesn't exist.
+pub fn is_controllable(device_name: &str) -> bool {
+    LogindBackend::new(device_name.to_string()).max_brightness().is_ok()
+}
+
+/// Pick the first controllable device out of `candidates`, in order.
+/// `None` if none of them are, letting the caller decide (per `--strict`)
+/// whether that's fatal or just a best-effort fall-through to the first
+/// candidate anyway.
+pub fn select_device(candidates: &[String]) -> Option<String> {
+    candidates.iter().find(|name| is_controllable(name)).cloned()
+}
@@ -0,0 +1,75 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use pipewire::loop_::LoopRef;
+
+/// How often the supervisor timer checks whether the continuous-on
+/// duration has crossed the warn threshold. Coarser than the PWM/
+/// rate-limit timers since this is a privacy safety-net, not something
+/// that needs sub-second responsiveness.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Tracks how long the LED has been continuously on, for `--long-session-warn`.
+/// Warns (once per continuous-on stretch) as a privacy safety-net against
+/// an app that forgot to release the camera.
+pub struct LongSessionWarn {
+    threshold: Duration,
+    on_since: RefCell<Option<Instant>>,
+    warned: Cell<bool>,
+}
+
+impl LongSessionWarn {
+    pub fn new(threshold: Duration) -> Rc<Self> {
+        Rc::new(Self {
+            threshold,
+            on_since: RefCell::new(None),
+            warned: Cell::new(false),
+        })
+    }
+
+    /// Call whenever the aggregate LED-on state is (re)computed, to track
+    /// when the current continuous-on stretch started.
+    pub fn note(&self, led_on: bool) {
+        let mut on_since = self.on_since.borrow_mut();
+        if led_on {
+            if on_since.is_none() {
+                *on_since = Some(Instant::now());
+                self.warned.set(false);
+            }
+        } else {
+            *on_since = None;
+            self.warned.set(false);
+        }
+    }
+
+    /// Returns how long the LED has been continuously on if it just
+    /// crossed `threshold` and hasn't already been warned about this
+    /// stretch, else `None`.
+    fn due(&self) -> Option<Duration> {
+        if self.warned.get() {
+            return None;
+        }
+        let elapsed = self.on_since.borrow().as_ref()?.elapsed();
+        if elapsed >= self.threshold {
+            self.warned.set(true);
+            Some(elapsed)
+        } else {
+            None
+        }
+    }
+}
+
+/// Start a supervisor timer (see `pwm.rs`/`ratelimit.rs` for why this is
+/// a single always-ticking timer rather than one armed per state change)
+/// that calls `notify` once a continuous-on stretch crosses the
+/// configured threshold.
+pub fn start(loop_: &LoopRef, warn: Rc<LongSessionWarn>, notify: impl Fn(Duration) + 'static) {
+    let timer = loop_.add_timer(move |_expirations| {
+        if let Some(elapsed) = warn.due() {
+            notify(elapsed);
+        }
+    });
+    let _ = timer.update_timer(Some(CHECK_INTERVAL), Some(CHECK_INTERVAL));
+    std::mem::forget(timer);
+}
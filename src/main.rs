@@ -1,25 +1,1670 @@
 use anyhow::Context;
 use pipewire::{
     loop_::Signal,
-    node::{Node, NodeListener, NodeState},
+    node::{Node, NodeListener},
     proxy::{Listener, ProxyListener, ProxyT},
+    spa::param::ParamType,
     types::ObjectType,
 };
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use zbus::blocking::Connection;
 use zbus::zvariant::Value;
 
+mod als;
+mod aux;
+mod check;
+mod cluster;
+mod combine;
+mod completions;
+mod config;
+mod debounce;
+mod event_csv;
+mod force;
+mod health;
+mod history;
+mod i18n;
+mod kernel_trigger;
+mod latch;
+mod led;
+mod longsession;
+mod notify_action;
+mod notify_fallback;
+mod pulse;
+mod pwm;
+mod ratelimit;
+mod replay;
+mod rules;
+mod session_scope;
+mod sessionlock;
+mod sound;
+mod state_file;
+mod suspend;
+mod sync;
+mod systemd;
+mod watch;
+mod writer;
+
+use led::LedBackend;
+
 const X13S_CAMERA_PRODUCT_NAME: &str = "ov5675";
 const X13S_LED_DEVICE_NAME: &str = "white:camera-indicator";
 const X13S_LED_BRIGHTNESS_ON: u32 = 1;
 const X13S_LED_BRIGHTNESS_OFF: u32 = 0;
+/// `replaces_id` used for every notification this daemon sends, so a new
+/// one always replaces the last rather than piling up. Also what
+/// `--persist-error-status` closes once a write succeeds.
+pub(crate) const ERROR_NOTIFICATION_ID: u32 = 42;
+
+/// What to do with the LED when the daemon exits.
+#[derive(PartialEq, Eq)]
+enum OffOnExit {
+    /// Don't touch the LED at shutdown (default, preserves prior behavior).
+    LeaveAsIs,
+    /// Turn the LED off at shutdown, but only if our own last write was
+    /// the one that turned it on — avoids stomping on an LED state set
+    /// by some other process.
+    IfWeTurnedItOn,
+}
+
+/// What to leave the LED showing while this daemon itself isn't running
+/// — e.g. the brief gap `systemctl restart` leaves between this process
+/// exiting and the replacement one attaching to PipeWire — see
+/// `--shutdown-indicator`. Applied after `--off-on-exit` at the very end
+/// of `monitor()`, so it can override `--off-on-exit`'s "only if we
+/// turned it on" guard with an unconditional final state when that's
+/// not cautious enough.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShutdownIndicator {
+    /// Leave whatever `--off-on-exit` left the LED at; no extra
+    /// shutdown-specific behavior (default, preserves prior behavior).
+    None,
+    /// Force the LED off on exit, unconditionally — unlike
+    /// `--off-on-exit=if-we-turned-it-on`, this doesn't check whether we
+    /// were the one who turned it on, for services that would rather
+    /// never risk looking falsely-on during the gap.
+    Off,
+    /// Blink the LED three times, then leave it off — a visible
+    /// "the monitor is restarting" signal, distinguishable from a
+    /// steady-off LED (which could just mean "no camera in use").
+    Blink,
+}
+
+/// What to do, beyond logging, when the PipeWire core reports an `error`
+/// for a non-global (non-zero) id that matches a currently-tracked node,
+/// see `--on-node-error`. A global (`id == 0`) error is unrelated to this
+/// — that's a connection-level failure and always quits the main loop
+/// regardless of this setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeErrorAction {
+    /// Log only (default), the prior behavior for non-global errors.
+    Log,
+    /// Also turn that node's entry in `camera_states` to `Error` (which
+    /// `cfg.brightness_map` maps to off by default) and write the
+    /// resulting brightness immediately, rather than waiting for a
+    /// later `info` event to notice the node is gone.
+    LedOff,
+    /// Also send a desktop notification, same `notification()` mechanism
+    /// `--persist-error-status` uses for a failed write.
+    Notify,
+}
+
+/// How the RGB and IR (face-unlock) front-camera nodes jointly drive the
+/// LED, see `--ir-lighting-policy`. Distinct from `Config::exclude_ir`,
+/// which controls whether the IR node can be *identified* as the tracked
+/// camera at all; this instead controls how an already-tracked IR node's
+/// activity feeds into `desired_brightness`'s aggregate once it's tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IrLightingPolicy {
+    /// Light the LED if either the RGB or the IR node is active
+    /// (default) — the prior behavior, unchanged for trees that don't
+    /// care about the distinction.
+    Either,
+    /// Only the RGB node's activity lights the LED; an active IR node
+    /// (e.g. Windows-Hello-style face unlock) is tracked and visible in
+    /// `camera_states`/`--watch`/`--status` as usual, but never
+    /// contributes to the aggregate.
+    RgbOnly,
+    /// Like `RgbOnly`, but additionally floors `desired_brightness`'s
+    /// aggregate to off whenever no RGB node is currently active, rather
+    /// than simply excluding IR nodes from the `max()` the way `RgbOnly`
+    /// does. The two differ once a non-`Active` state maps to a nonzero
+    /// brightness (`--standby-brightness`, `--early-on`,
+    /// `--state-brightness`): `RgbOnly` still shows that value for an idle
+    /// RGB node, while `RequireRgb` keeps the LED off until an RGB node is
+    /// `Active`. `--force-state-file` and the other
+    /// clamps in `monitor()` still apply afterward, same as always.
+    RequireRgb,
+}
+
+struct Args {
+    /// Skip camera identification and drive the LED from the aggregate
+    /// state of every `Video/Source` camera node.
+    any_camera: bool,
+    /// Print a live-updating camera/LED status block to the terminal.
+    watch: bool,
+    off_on_exit: OffOnExit,
+    /// See `ShutdownIndicator`/`--shutdown-indicator`.
+    shutdown_indicator: ShutdownIndicator,
+    /// The PipeWire remote to connect to, e.g. for a sandboxed instance.
+    /// Falls back to the `PIPEWIRE_REMOTE` env var, then the default remote.
+    pipewire_remote: Option<String>,
+    /// Exclude the IR (face-unlock) camera from identification.
+    exclude_ir: bool,
+    /// Log every registry `global`/`global_remove` event at info level.
+    trace_registry: bool,
+    /// Log the input (`camera_states`, config) and output (final
+    /// brightness, write issued or coalesced) of every LED decision, for
+    /// "LED did the wrong thing" bug reports. See `desired_brightness`
+    /// and `log_state_machine_trace`. A finer-grained sibling of
+    /// `trace_registry`, which only logs that a registry event happened,
+    /// not what the daemon decided to do about it.
+    trace_state_machine: bool,
+    /// Emulate dimming on a binary LED by rapidly toggling it with this
+    /// duty cycle (0-100), instead of driving it fully on. `None` means
+    /// drive the LED at full brightness as before (no software PWM).
+    duty: Option<u8>,
+    /// Bind the given node id, print its props and state once known, and
+    /// exit. A targeted alternative to watching the whole registry when
+    /// the suspect id is already known (e.g. from `pw-dump`).
+    dump_node: Option<u32>,
+    /// Suppress LED writes while logind reports the system is preparing
+    /// for or recovering from sleep, to avoid a flicker as PipeWire
+    /// re-enumerates the camera across suspend/resume.
+    smooth_suspend: bool,
+    /// Minimum time, in milliseconds, between actual `SetBrightness`
+    /// calls. `None` (default) means no rate limiting. A value that
+    /// arrives sooner is coalesced and flushed once the interval elapses.
+    min_write_interval: Option<u64>,
+    /// Path for a liveness-check Unix socket. `None` (default) means the
+    /// health check is disabled. A relative path is resolved under
+    /// systemd's runtime directory, see `systemd::resolve_runtime_path`.
+    health_socket: Option<std::path::PathBuf>,
+    /// Node ids or `node.name`/`node.description` values to never treat
+    /// as the camera, regardless of how well their props otherwise match.
+    /// An immediate workaround for a spuriously-matching node.
+    ignore_nodes: Vec<String>,
+    /// Pin the tracked camera to the node whose `object.path` equals
+    /// this, bypassing the heuristic predicate entirely. `object.path`
+    /// is stable across restarts for the same hardware, unlike the
+    /// numeric node id.
+    pin_object_path: Option<String>,
+    /// Warn (or with `--strict`, exit non-zero) if no camera matches the
+    /// identification rule within this long of starting up. `None`
+    /// (default) disables the check.
+    expect_camera_within: Option<Duration>,
+    /// Exit non-zero instead of only warning when `--expect-camera-within`
+    /// elapses with no matching camera.
+    strict: bool,
+    /// Print the effective configuration (identification rule and
+    /// `--ignore-node` list) and exit, without connecting to PipeWire.
+    print_config: bool,
+    /// Run the D-Bus/logind/LED-device preflight checklist and exit.
+    check_session: bool,
+    /// Prefer logind's percentage-based `SetBrightnessPercentage` over
+    /// the absolute `SetBrightness`, on versions that have it.
+    brightness_percentage: bool,
+    /// Send a notification if the LED has been continuously on for
+    /// longer than this, as a privacy safety-net against an app that
+    /// forgot to release the camera. `None` (default) disables it.
+    long_session_warn: Option<Duration>,
+    /// Secondary LEDs to mirror the camera LED's on/off state onto, each
+    /// with its own on/off values and optional inversion. Errors on an
+    /// aux LED are logged but never fatal to the primary one.
+    aux_leds: Vec<String>,
+    /// Log a per-predicate pass/fail explanation for every camera-class
+    /// node on each `info` event. The running version of `--check-session`
+    /// for "why doesn't this node match" reports.
+    explain: bool,
+    /// Keep the last write-error notification on screen instead of
+    /// letting it expire, and close it as soon as a write succeeds.
+    persist_error_status: bool,
+    /// Where a write-error notification should go when the session bus
+    /// `notification()` needs isn't available at all (e.g. a headless
+    /// box), see `notify_fallback`. `None` (default) just logs the
+    /// failure, the prior behavior.
+    notify_fallback: Option<notify_fallback::NotifyFallback>,
+    /// Per-`application.name` software-PWM duty cycles (0-100), applied
+    /// while that app holds the tracked camera; `duty` is the default
+    /// pattern when no entry matches.
+    app_patterns: HashMap<String, u8>,
+    /// Ignore PipeWire entirely and toggle the LED on this fixed (on,
+    /// off) schedule, for demos/showroom units. `None` (default) means
+    /// real monitoring.
+    simulate: Option<(Duration, Duration)>,
+    /// `media.role`/`media.class` values to exclude from identification,
+    /// checked ahead of every inclusion predicate, same as `ignore_nodes`.
+    exclude_roles: Vec<String>,
+    /// Offload software-PWM blinking to the kernel's `timer` LED trigger
+    /// instead of a userspace timer, when the trigger is available for
+    /// the LED device. Falls back to the software timer otherwise.
+    use_kernel_trigger: bool,
+    /// Overrides onto `config::default_brightness_map()`, keyed by
+    /// `CameraState` name (active/inactive/error/unknown). Lets e.g.
+    /// `inactive` dim rather than fully extinguish the LED.
+    state_brightness: HashMap<String, u32>,
+    /// Candidate LED device names to probe in order at startup, using the
+    /// first controllable one; empty (default) means just
+    /// `X13S_LED_DEVICE_NAME`, the prior hardcoded behavior. Removes
+    /// guesswork across hardware revisions with differently-named LEDs
+    /// (e.g. `white:camera-indicator` vs `platform::camera`).
+    led_devices: Vec<String>,
+    /// Warn if the tracked-node map grows beyond this many entries, a
+    /// defensive backstop against a listener leak or pathological node
+    /// churn. Default is high enough to never trigger under normal use.
+    max_nodes: usize,
+    /// With `max_nodes`, additionally prune the oldest tracked non-camera
+    /// node once the bound is exceeded, instead of only warning.
+    prune_excess_nodes: bool,
+    /// Treat `Creating` (pre-`Running` initialization) as active too,
+    /// lighting the LED as soon as the node starts coming up rather than
+    /// only once it's fully `Running`. Default keeps `Running`-only.
+    early_on: bool,
+    /// Force `env_logger`'s colorization on/off, or leave it to its
+    /// usual TTY-detection default (`Auto`).
+    color_log: env_logger::WriteStyle,
+    /// Run identification and the brightness mapping against a recorded
+    /// `pw-dump` JSON snapshot instead of a live PipeWire connection, and
+    /// exit. `None` (default) means normal live monitoring.
+    replay: Option<std::path::PathBuf>,
+    /// Run identification and the brightness mapping against a
+    /// `--replay-states` script (one `<id> <state> [key=value ...]` event
+    /// per line, fed through the same decision path sequentially) instead
+    /// of a live PipeWire connection, and exit. Lighter-weight than
+    /// `--replay`'s single `pw-dump` snapshot — for scripting flapping,
+    /// removal, and state-before-props edge cases deterministically. See
+    /// `replay::run_states`. `None` (default) means normal live monitoring.
+    replay_states: Option<std::path::PathBuf>,
+    /// Convenience for `--state-brightness inactive=<value>`: the level
+    /// to show while a tracked camera is present but idle (`Inactive`),
+    /// as opposed to fully off when no camera is tracked at all. `None`
+    /// (default) keeps the old two-state behavior (standby = off). An
+    /// explicit `--state-brightness inactive=...` takes precedence over
+    /// this if both are given.
+    standby_brightness: Option<u32>,
+    /// On a multi-user machine, never track a node whose `pipewire.sec.uid`
+    /// names a different user than the one running this daemon. Off by
+    /// default (current behavior: any matching node is tracked regardless
+    /// of owner) since most installs are single-user and the security
+    /// module that stamps the property isn't always enabled.
+    only_my_nodes: bool,
+    /// On a fast-user-switching machine, further restrict which logged-in
+    /// user's camera drives the LED: `any` (default, prior behavior) for
+    /// no restriction, `foreground` to require that user's session be the
+    /// active one on some seat, `seat` to require it be active on this
+    /// process's own seat specifically. See `session_scope` for the
+    /// logind-correlation mechanism and its limits.
+    session_scope: session_scope::SessionScope,
+    /// `application.name` values allowed to drive the LED, e.g. to ignore
+    /// a background probe that briefly opens the camera while still
+    /// reacting to trusted video-call apps. Empty (default) means all
+    /// apps, the prior behavior. There's no deny-list counterpart in this
+    /// tree; `--ignore-node`/`--exclude-role` are the closest existing
+    /// mechanisms for excluding specific things outright.
+    app_allowlist: Vec<String>,
+    /// Built-in hardware preset to start from (`x13s`/`thinkpad-generic`/
+    /// `uvc-desktop`, see `config::PROFILES`), validated at parse time.
+    /// `None` (default) keeps the hardcoded X13s defaults, same as a
+    /// literal `--profile x13s` would.
+    profile: Option<String>,
+    /// Override `Config::camera_product_name` (`device.product.name` to
+    /// match), instead of the profile's or the hardcoded default.
+    product_name: Option<String>,
+    /// Override `Config::front_location` (`api.libcamera.location` to
+    /// match), instead of the profile's or the hardcoded default.
+    front_location: Option<String>,
+    /// `Config::pipeline_handler`: an optional `api.libcamera.PipelineHandler`
+    /// value to additionally require a match on. `None` (default) doesn't
+    /// check this prop at all, same as before this flag existed — unlike
+    /// `product_name`/`front_location`, there's no hardcoded default to
+    /// fall back to, since not every libcamera version/driver reports this
+    /// prop.
+    pipeline_handler: Option<String>,
+    /// `Config::device_api`: an optional `device.api` value (e.g.
+    /// `libcamera`, `v4l2`) to additionally require a match on, so only
+    /// one of a libcamera/v4l2 node pair for the same physical camera
+    /// drives the LED. `None` (default) matches both APIs, same as
+    /// before this flag existed.
+    device_api: Option<String>,
+    /// `Config::device_serial`: an optional `device.serial` or
+    /// `api.v4l2.cap.bus_info` value to additionally require a match on,
+    /// for pinning to one specific physical USB webcam when several are
+    /// plugged in. `None` (default) doesn't check this prop at all, same
+    /// as before this flag existed.
+    device_serial: Option<String>,
+    /// What to do, beyond logging, when the PipeWire core reports an
+    /// `error` for a non-global id that's a currently-tracked node, see
+    /// `NodeErrorAction`/`--on-node-error`. `Log` (default) keeps the
+    /// prior log-only behavior for non-global errors.
+    on_node_error: NodeErrorAction,
+    /// Shell to generate a flag-completion script for, see
+    /// `--completions`/`completions::generate`. `None` (default) runs the
+    /// daemon normally. Not a subcommand — this crate has no subcommand
+    /// concept, only flag-driven modes like `--status`/`--dump-node`, and
+    /// this follows the same shape rather than adopting `clap` wholesale
+    /// just to get `clap_complete`.
+    completions: Option<String>,
+    /// Path to read/write the persisted camera identity, see
+    /// `state_file::PersistedIdentity`. `None` (default) neither reads nor
+    /// writes one, same as before this flag existed. A relative path is
+    /// resolved under systemd's runtime directory, see
+    /// `systemd::resolve_runtime_path`.
+    state_file: Option<String>,
+    /// Suppress (log but don't apply) LED-on writes for this long after
+    /// startup, to ride out transient boot/login-time camera probes (e.g.
+    /// a greeter) without flickering the LED. Unlike `startup_delay`,
+    /// which delays connecting to PipeWire at all, this connects and
+    /// tracks normally throughout — only the *on* write is held back, and
+    /// only within the window. `None`/zero (default) suppresses nothing.
+    startup_quiet: Option<Duration>,
+    /// Path polled (see `force::Force`) for a manual LED override, for
+    /// `--force-state-file`. `None` (default) polls nothing — automatic
+    /// control only, same as before this flag existed. A relative path is
+    /// resolved under systemd's runtime directory, see
+    /// `systemd::resolve_runtime_path`.
+    force_state_file: Option<String>,
+    /// Append one row per camera state change (timestamp, camera id,
+    /// product, app, new state, resulting brightness) to this CSV file,
+    /// see `event_csv::EventCsv`. A focused observability output distinct
+    /// from the JSON status/logging elsewhere. `None` (default) logs
+    /// nothing. A failure to open the file at startup is logged and
+    /// disables this flag for the run rather than aborting the daemon. A
+    /// relative path is resolved under systemd's runtime directory, see
+    /// `systemd::resolve_runtime_path`.
+    event_csv: Option<String>,
+    /// Capacity of the in-memory ring buffer of recent state-change
+    /// records, see `history::History`. `0` disables it outright. A
+    /// bounded, in-process complement to `--event-csv`'s durable file —
+    /// not yet queryable from outside this process (see `history`'s
+    /// module doc comment for why), so this only matters once something
+    /// reads it, which nothing does yet.
+    history_size: usize,
+    /// Path to a sound file to play (via `aplay`) when the tracked
+    /// camera's LED transitions from off to on. `None` (default) plays
+    /// nothing, same as before this flag existed.
+    sound_on: Option<String>,
+    /// Path to a sound file to play when the LED transitions from on to
+    /// off. `None` (default) plays nothing.
+    sound_off: Option<String>,
+    /// Minimum score (out of `config::MatchWeights::total()`) for the
+    /// scored matcher (`rules::matches_camera_scored`) to consider a node
+    /// the camera, instead of `matches_camera`'s strict AND. `None`
+    /// (default) keeps the strict predicate.
+    match_threshold: Option<f64>,
+    /// Overrides onto `config::MatchWeights::default()`, keyed by
+    /// predicate name (media_role/location/product_name/not_ir/
+    /// pipeline_handler/device_api/device_serial). Only
+    /// meaningful together with `match_threshold`.
+    match_weights: HashMap<String, f64>,
+    /// Log and count "probe without stream" events: a tracked node going
+    /// `Creating` then straight to `Idle`/`Suspended` without ever
+    /// reaching `Running`, e.g. some background process briefly probing
+    /// the camera without actually opening a stream. Off by default,
+    /// since it's an observability aid rather than something that should
+    /// change LED behavior.
+    debug_probe_without_stream: bool,
+    /// Only treat a tracked node as `Active` once it has a negotiated
+    /// `Format` param, not merely `Running` — a precision improvement
+    /// distinguishing a real capture (specific resolution/framerate
+    /// negotiated) from a trivial probe that opens the device without
+    /// ever configuring a stream. Off by default, preserving the prior
+    /// state-only behavior; see `node_has_format` in `monitor()`.
+    require_format: bool,
+    /// Read the LED's sysfs `brightness` back after every write and warn
+    /// if it doesn't match what was just written, catching something
+    /// else (e.g. a kernel trigger) silently overriding it. Off by
+    /// default: an extra sysfs read per write that most setups don't need.
+    verify_write: bool,
+    /// Warn when the time from a camera state change being queued for
+    /// write to that write actually completing exceeds this, see
+    /// `writer::LedWriter::spawn`'s `max_event_latency_warn`. Surfaces a
+    /// D-Bus or sysfs backend slow enough to be user-noticeable. `None`
+    /// (default) never checks this.
+    max_event_latency_warn: Option<Duration>,
+    /// LED device to light while any node matching `rules::matches_screencast`
+    /// is present, independent of the primary camera LED. `None` (default)
+    /// disables screencast tracking entirely, keeping the default
+    /// camera-only behavior. Runs its own `LedWriter`, same as an aux LED.
+    screencast_led: Option<String>,
+    /// Send a notification when the screencast LED (`--screencast-led`)
+    /// transitions on or off. No effect without `--screencast-led`.
+    screencast_notify: bool,
+    /// Settle window the screencast LED's on/off aggregate must hold
+    /// steady for before committing, via `debounce::Debounce` - filters
+    /// out brief flaps (e.g. a screen-share app briefly recreating its
+    /// capture node). `None` (default) commits instantly, the same as
+    /// before this flag existed.
+    screencast_debounce: Option<Duration>,
+    /// LED device to light while any node matching `rules::matches_audio_sink`
+    /// is in the running state, independent of the primary camera LED -
+    /// same "opt-in second `LedWriter`" shape as `screencast_led`. `None`
+    /// (default) disables audio-playback tracking entirely.
+    audio_led: Option<String>,
+    /// Send a notification, naming the playing app if known, when the
+    /// audio LED (`--audio-led`) transitions on or off. No effect without
+    /// `--audio-led`.
+    audio_notify: bool,
+    /// Same debounce settle window as `screencast_debounce`, applied
+    /// independently to the audio LED's on/off aggregate. `None`
+    /// (default) commits instantly.
+    audio_debounce: Option<Duration>,
+    /// Send a notification, with a "Disable camera LED" action button,
+    /// each time the tracked camera's LED transitions off to on.
+    /// Clicking the action permanently disables further LED writes for
+    /// the rest of this run (see `notify_action` and the `led_disabled`
+    /// flag in `monitor()`) — there's no matching "re-enable" action,
+    /// since that would need a resumable pause/resume mechanism this
+    /// tree doesn't have yet (see the D-Bus `Pause()`/`Resume()` note
+    /// earlier in `monitor()`). Off by default.
+    camera_notify: bool,
+    /// How the RGB and IR front-camera nodes jointly drive the LED once
+    /// both are tracked, see `IrLightingPolicy`/`--ir-lighting-policy`.
+    /// `Either` (default) keeps the prior behavior.
+    ir_lighting_policy: IrLightingPolicy,
+    /// Send a notification when a tracked camera's access transitions to
+    /// or from being mediated by an xdg-desktop-portal (e.g. a Flatpak
+    /// app), see `rules::is_sandboxed`. Off by default; purely
+    /// informational - it doesn't otherwise change identification or LED
+    /// behavior, since portal-mediated access is still either the RGB or
+    /// IR node underneath and already handled as such.
+    notify_sandboxed: bool,
+    /// Re-bind and re-check every `Video/Source` node we've seen on this
+    /// interval, re-running the same identification a fresh `info` event
+    /// would trigger. A safety net for environments where prop delivery is
+    /// unreliable: the per-node handler already re-checks on every `info`
+    /// event it receives, but a node whose first (and only) delivery had
+    /// incomplete or misleading props never gets a second chance without
+    /// this. `None` (default) disables rediscovery entirely - the prior
+    /// behavior.
+    rediscover_interval: Option<Duration>,
+    /// Sleep this long before the first `Context::connect`, e.g. to give
+    /// a PipeWire session that starts later in a boot sequence time to
+    /// come up before we try to reach it. `None` (default) means connect
+    /// immediately, the prior behavior. There's no connect-retry feature
+    /// in this tree to combine this with — a failed `context.connect` is
+    /// still a hard error today, same as before this flag existed.
+    startup_delay: Option<Duration>,
+    /// Render a breathing brightness curve on a dimmable LED while a
+    /// tracked camera is active, instead of driving it fully on (or the
+    /// fixed-duty software PWM of `--duty`). Off by default. Takes
+    /// priority over `--duty`/`--app-pattern` when both are given, since
+    /// only one timer can own writing the LED.
+    pulse: bool,
+    /// Curve shape for `--pulse`: `sine`, `triangle`, or
+    /// `keyframes:<level>,<level>,...` (each `0.0..=1.0`). Defaults to
+    /// `sine` when `--pulse` is given without this.
+    pulse_curve: Option<String>,
+    /// Period of one full `--pulse` cycle, in milliseconds. Default 2000.
+    pulse_period: Duration,
+    /// Scale the "on" brightness by ambient light (read from an IIO ALS
+    /// sensor, see `als::read_lux`) within these `min:max` bounds, instead
+    /// of driving the LED fully on. `None` (default) disables this
+    /// entirely. Same "owns writing the LED while active" priority as
+    /// `--pulse`, and mutually exclusive with it for the same reason: only
+    /// one timer can own writing the LED.
+    als_scale: Option<als::AlsScale>,
+    /// Hidden: quit after this many camera-state transitions, printing
+    /// each one as it's observed. For CI smoke tests that want a
+    /// deterministic exit from a real monitor run against a real/mock
+    /// graph, instead of having to kill the process externally. `None`
+    /// (default) never quits on its own.
+    count: Option<u64>,
+    /// Delegate LED control to an external command instead of logind,
+    /// with `{brightness}` substituted by the value to write, e.g.
+    /// `/usr/local/bin/setled {brightness}`. Run through `sh -c`. `None`
+    /// (default) keeps using `--led-device`/logind as before.
+    led_command: Option<String>,
+    /// `max_brightness` to report for `--led-command`'s backend, which
+    /// has no sysfs device of its own to read it from. Default 1
+    /// (on/off only).
+    led_command_max: u32,
+    /// A sysfs LED device name to fall back to (via a second
+    /// `LogindBackend`) if the primary backend (`--led-command`/
+    /// `--gpio-chip`/plain `--led-device`) fails a write, wrapping both
+    /// in `led::FallbackLedBackend`. `None` (default): no fallback, a
+    /// failed write is just a failed write, as before this flag existed.
+    fallback_led_device: Option<String>,
+    /// Suppress lighting the LED (forcing it off instead) while the
+    /// logind session is locked, per `org.freedesktop.login1.Session`'s
+    /// `LockedHint`. Off by default: a locked session doesn't change LED
+    /// behavior, the prior behavior. Only gates the primary LED's direct
+    /// write/aux-LED-mirror/sound-cue path — `--duty`'s software PWM and
+    /// `--pulse` drive the LED from `camera_states` directly and don't
+    /// observe this yet, same existing seam as those two not observing
+    /// the rate limiter either.
+    only_when_unlocked: bool,
+    /// Edge- rather than level-triggered LED logic, per `--latch`: once
+    /// lit, the LED stays on past the point where the underlying decision
+    /// would otherwise have turned it off, until the clear condition
+    /// here fires. `None` (default) keeps the prior, level-triggered
+    /// behavior. See `latch` for the clear conditions and what was
+    /// deliberately left out (a D-Bus manual clear).
+    latch_clear: Option<latch::LatchClear>,
+    /// Timeout for blocking D-Bus calls (logind's `SetBrightness`/
+    /// `SetBrightnessPercentage`/`Introspect`, and the notification
+    /// daemon's `Notify`/`CloseNotification`). On timeout, the call is
+    /// logged and abandoned rather than left to block the LED writer
+    /// thread (or, for notifications, the PipeWire main loop) forever on
+    /// a hung bus. `None` (default) keeps zbus's own defaults.
+    dbus_timeout: Option<Duration>,
+    /// Sysfs gpiochip path (e.g. `/sys/class/gpio/gpiochip0`) to mirror
+    /// camera state to instead of an onboard LED, via [`led::GpioBackend`]
+    /// (only present in binaries built with the `gpio` cargo feature).
+    /// `None` (default) keeps using `--led-device`/logind as before.
+    gpio_chip: Option<String>,
+    /// Line offset within `--gpio-chip`. Defaults to 0 if `--gpio-chip`
+    /// is given without this.
+    gpio_line: Option<u32>,
+    /// Accept connections from `--cluster-peer`s on this address
+    /// (`host:port`) and tell them our own active/inactive state, for
+    /// aggregating several machines' cameras onto one combined LED (see
+    /// `cluster.rs`). `None` (default) doesn't listen.
+    cluster_listen: Option<String>,
+    /// Connect to a peer daemon's `--cluster-listen` address and light
+    /// our own LED whenever it reports its camera active, in addition to
+    /// our own. Repeatable for more than one peer.
+    cluster_peers: Vec<String>,
+    /// Print a one-shot snapshot of every camera-role node and the LED
+    /// device, then exit. See `status()`.
+    status: bool,
+    /// With `--status`, print the machine-readable
+    /// `{cameras:[...], led:{...}}` form instead of a human checklist.
+    status_json: bool,
+}
+
+/// Parse a systemd-style duration suffix: a plain integer is seconds,
+/// otherwise `s`/`m`/`h` select the unit (e.g. `90s`, `30m`, `2h`).
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value
+        .find(|c: char| !c.is_ascii_digit())
+    {
+        Some(split) => value.split_at(split),
+        None => (value, "s"),
+    };
+    let number: u64 = number.parse().map_err(|_| format!("invalid duration: {}", value))?;
+    let seconds = match unit {
+        "s" | "" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        other => return Err(format!("unknown duration unit {:?} in {:?}", other, value)),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Our own real uid, for `--only-my-nodes` to compare a node's
+/// `pipewire.sec.uid` against. No `libc`/`rustix`/`nix` dependency exists
+/// in this tree for a direct `getuid()`, so this reads it off `/proc/self`
+/// instead, which is owned by the calling process by definition. `None`
+/// if `/proc` isn't mounted (uncommon outside unusual containers).
+fn current_uid() -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata("/proc/self").map(|m| m.uid()).ok()
+}
+
+/// The core "what should the LED show right now" decision: the brightest
+/// mapped value across every tracked camera's state (see
+/// `Config::brightness_for`), or off when nothing's tracked. Everything
+/// else the `info`/`global_remove` handlers do after calling this
+/// (`--cluster-listen`, `--only-when-unlocked`, `--startup-quiet`,
+/// `--force-state-file`) is a further clamp layered on top - this is
+/// deliberately just the piece whose only inputs are `camera_states`,
+/// `camera_is_ir`, `policy` and `cfg`, pulled out so `--trace-state-machine`
+/// (see [`log_state_machine_trace`]) has one well-defined function call to
+/// log the input/output of, instead of re-deriving "the decision" from
+/// whatever clamp chain happens to surround it at each call site.
+///
+/// `camera_is_ir`/`policy` implement `--ir-lighting-policy`
+/// (`IrLightingPolicy`): under `Either` (default) every tracked node
+/// contributes, same as before that flag existed; under `RgbOnly` and
+/// `RequireRgb`, IR-classified ids are excluded from the aggregate, so an
+/// active IR-only node can't light the LED on its own. `RequireRgb` also
+/// returns off outright unless at least one non-IR id is `Active`.
+fn desired_brightness(
+    camera_states: &HashMap<u32, rules::CameraState>,
+    camera_is_ir: &HashMap<u32, bool>,
+    policy: IrLightingPolicy,
+    cfg: &config::Config,
+) -> u32 {
+    if policy == IrLightingPolicy::RequireRgb
+        && !camera_states.iter().any(|(id, state)| {
+            state.is_active() && !camera_is_ir.get(id).copied().unwrap_or(false)
+        })
+    {
+        return X13S_LED_BRIGHTNESS_OFF;
+    }
+    camera_states
+        .iter()
+        .filter(|(id, _)| {
+            policy == IrLightingPolicy::Either || !camera_is_ir.get(*id).copied().unwrap_or(false)
+        })
+        .map(|(_, state)| cfg.brightness_for(*state))
+        .max()
+        .unwrap_or(X13S_LED_BRIGHTNESS_OFF)
+}
+
+/// Every override clamp layered on top of [`desired_brightness`]'s
+/// unclamped aggregate, in the order they apply: `--cluster-listen`/
+/// `--cluster-peer` (broadcasts first, then floors to on if a peer is
+/// active), `--only-when-unlocked` (forces off), `--startup-quiet`
+/// (suppresses an *on* write within the startup window), `--latch`
+/// (edge-triggered hold), `--force-state-file` (a manual override that
+/// wins over every computed clamp above it), and finally
+/// `--camera-notify`'s "Disable camera LED" action (wins over even a
+/// forced override). Also notes the result to `--long-session-warn`'s
+/// tracker, since that needs to see every write this feeds, not just
+/// the ones from one particular call site.
+///
+/// Previously hand-duplicated between the `info` and `global_remove`
+/// registry handlers, which let `global_remove`'s copy silently drift
+/// out of sync with `--startup-quiet` (added after the duplication) -
+/// both now call this one function so they can't diverge again, same
+/// "pull the shared piece out from under drifting call sites" shape as
+/// [`desired_brightness`] itself.
+fn clamp_led_brightness(
+    desired: u32,
+    cluster: Option<&cluster::Cluster>,
+    session_locked: Option<&std::sync::atomic::AtomicBool>,
+    started_at: std::time::Instant,
+    startup_quiet: Option<Duration>,
+    latch: Option<&latch::Latch>,
+    force: Option<&force::Force>,
+    led_disabled: bool,
+    long_session_warn: Option<&longsession::LongSessionWarn>,
+) -> u32 {
+    let led_brightness = desired;
+    // `--cluster-listen`/`--cluster-peer`: tell any connected peer our
+    // own state, and light our LED if any peer of ours reports theirs
+    // active, same "brightest wins" spirit as combining this daemon's
+    // own tracked nodes above.
+    let led_brightness = if let Some(cluster) = cluster {
+        cluster.broadcast(led_brightness == X13S_LED_BRIGHTNESS_ON);
+        if cluster.peer_active() {
+            led_brightness.max(X13S_LED_BRIGHTNESS_ON)
+        } else {
+            led_brightness
+        }
+    } else {
+        led_brightness
+    };
+    // `--only-when-unlocked`: force off rather than skip the write, so a
+    // locked session's LED actually goes dark instead of sitting on
+    // whatever it last showed.
+    let locked = session_locked.is_some_and(|flag| flag.load(Ordering::SeqCst));
+    let led_brightness = if locked { X13S_LED_BRIGHTNESS_OFF } else { led_brightness };
+    // `--startup-quiet`: ride out transient boot/login-time probes
+    // without flickering the LED. Only the *on* write is held back (off
+    // writes, including this clamp's own, always go through) -
+    // suppressing those too would just delay noticing a probe already
+    // ended, the opposite of what a quiet boot wants.
+    let led_brightness = if led_brightness == X13S_LED_BRIGHTNESS_ON
+        && startup_quiet.is_some_and(|quiet| started_at.elapsed() < quiet)
+    {
+        log::info!(
+            "--startup-quiet: suppressing LED-on {:?} into startup",
+            started_at.elapsed()
+        );
+        X13S_LED_BRIGHTNESS_OFF
+    } else {
+        led_brightness
+    };
+    // `--latch`: edge- rather than level-triggered logic, applied after
+    // `--only-when-unlocked`/`--startup-quiet` so a locked session or a
+    // quiet boot still wins over a latch still holding the LED on from
+    // before either kicked in.
+    let led_brightness = if let Some(latch) = latch {
+        if latch.apply(led_brightness == X13S_LED_BRIGHTNESS_ON, locked) {
+            X13S_LED_BRIGHTNESS_ON
+        } else {
+            led_brightness
+        }
+    } else {
+        led_brightness
+    };
+    // `--force-state-file`: a manual override wins over every computed
+    // clamp above, including `--only-when-unlocked` and
+    // `--startup-quiet` - it's an explicit "give me exactly this"
+    // request. `camera_states` is still updated normally by the caller,
+    // so automatic control picks up from real state the moment the
+    // override expires or is cleared.
+    let led_brightness = if let Some(forced) = force.and_then(|f| f.active()) {
+        forced
+    } else {
+        led_brightness
+    };
+    // `--camera-notify`'s "Disable camera LED" action: an explicit click
+    // just now outranks even `--force-state-file`, the same way that
+    // override outranks everything computed above it.
+    let led_brightness = if led_disabled { X13S_LED_BRIGHTNESS_OFF } else { led_brightness };
+    if let Some(warn) = long_session_warn {
+        warn.note(led_brightness == X13S_LED_BRIGHTNESS_ON);
+    }
+    led_brightness
+}
+
+/// `--trace-state-machine`: log the full input and output of one decision,
+/// for the "LED did the wrong thing" reports that are otherwise hard to
+/// reconstruct after the fact. `desired` is [`desired_brightness`]'s
+/// output before any clamp; `final_brightness` is what's left after every
+/// clamp the caller applies on top; `write_issued` is whether that value
+/// was actually sent to the writer or coalesced away (e.g. by
+/// `--min-write-interval`'s rate limiter, or suppressed entirely while
+/// `--pulse`/software PWM/a suspend transition owns the LED).
+fn log_state_machine_trace(
+    label: &str,
+    camera_states: &HashMap<u32, rules::CameraState>,
+    desired: u32,
+    final_brightness: u32,
+    write_issued: bool,
+) {
+    log::info!(
+        "--trace-state-machine: {} camera_states:{:?} desired:{} final:{} write:{}",
+        label,
+        camera_states,
+        desired,
+        final_brightness,
+        if write_issued { "issued" } else { "coalesced" },
+    );
+}
+
+/// Apply `--early-on`, `--standby-brightness`, and `--state-brightness`
+/// overrides onto `config::default_brightness_map()`, in that order, so
+/// an explicit `--state-brightness unknown=...`/`inactive=...` still wins
+/// over either of the other two's blanket substitutions. An override
+/// naming an unrecognized state is rejected up front (at arg parse time)
+/// rather than silently ignored.
+///
+/// There's no on-delay feature in this tree yet (a deliberate pause
+/// before actually lighting the LED) for `--early-on` to interact badly
+/// with; if one's ever added, it should short-circuit rather than fight
+/// this flag, since the point of `--early-on` is to *shrink* the window
+/// where the camera is active without the indicator, not race another
+/// delay in the other direction.
+fn build_brightness_map(
+    overrides: &HashMap<String, u32>,
+    early_on: bool,
+    standby_brightness: Option<u32>,
+) -> HashMap<rules::CameraState, u32> {
+    let mut map = config::default_brightness_map();
+    if early_on {
+        // `Unknown` is `Creating`'s mapped `CameraState` (see
+        // `rules::camera_state_from_node_state`) — light the LED as soon
+        // as the node starts initializing, same as a running one, rather
+        // than only once it reaches `Running`.
+        map.insert(rules::CameraState::Unknown, map[&rules::CameraState::Active]);
+    }
+    if let Some(brightness) = standby_brightness {
+        // Sugar for `--state-brightness inactive=<value>`, for the common
+        // case of just wanting a dimmer "standby" level instead of fully
+        // off while a tracked camera is present but idle, without having
+        // to know the `CameraState` name. An explicit
+        // `--state-brightness inactive=...` below still wins.
+        map.insert(rules::CameraState::Inactive, brightness);
+    }
+    for (state, brightness) in overrides {
+        match state.parse::<rules::CameraState>() {
+            Ok(state) => {
+                map.insert(state, *brightness);
+            }
+            Err(err) => {
+                eprintln!("--state-brightness: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+    map
+}
+
+/// Apply `--match-weight` overrides onto `config::MatchWeights::default()`,
+/// for `--match-threshold`'s scored matcher. An override naming an
+/// unrecognized predicate is rejected up front, same as
+/// `build_brightness_map`'s `--state-brightness` handling.
+fn build_match_weights(overrides: &HashMap<String, f64>) -> config::MatchWeights {
+    let mut weights = config::MatchWeights::default();
+    for (predicate, weight) in overrides {
+        match predicate.as_str() {
+            "media_role" => weights.media_role = *weight,
+            "location" => weights.location = *weight,
+            "product_name" => weights.product_name = *weight,
+            "not_ir" => weights.not_ir = *weight,
+            "pipeline_handler" => weights.pipeline_handler = *weight,
+            "device_api" => weights.device_api = *weight,
+            "device_serial" => weights.device_serial = *weight,
+            other => {
+                eprintln!(
+                    "--match-weight: unknown predicate {:?}, expected one of \
+                     media_role/location/product_name/not_ir/pipeline_handler/device_api/device_serial",
+                    other
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+    weights
+}
+
+/// Resolve `Config::camera_product_name`/`front_location`/`led_device_name`/
+/// `pipeline_handler` and the effective `any_camera` flag from, in
+/// increasing precedence: the hardcoded X13s defaults, `--state-file`'s
+/// persisted identity (if given and readable, see `state_file::load`),
+/// `--profile`'s preset (if given), then
+/// `--product-name`/`--front-location`/`--pipeline-handler`/`--led-device`/
+/// `--any-camera` explicit overrides. `--profile` was already validated at
+/// parse time, so an unknown name here can't happen. A stale or mismatched
+/// persisted identity isn't special-cased beyond this precedence: it's
+/// just a default that loses to any explicit flag/profile and otherwise
+/// behaves exactly like `--product-name`/`--front-location`/
+/// `--pipeline-handler` would, so a node that no longer matches it simply
+/// never gets tracked, the same as a wrong manual override would — normal
+/// re-discovery on the next `--state-file` write is what "handle gracefully"
+/// means here, there's no separate repair path.
+fn resolve_identification(args: &Args) -> (String, String, String, bool, Option<String>) {
+    let profile = args.profile.as_deref().and_then(config::profile);
+    let persisted = args.state_file.as_deref().and_then(state_file::load);
+    let camera_product_name = args
+        .product_name
+        .clone()
+        .or_else(|| profile.and_then(|p| p.camera_product_name).map(String::from))
+        .or_else(|| persisted.as_ref().and_then(|p| p.camera_product_name.clone()))
+        .unwrap_or_else(|| X13S_CAMERA_PRODUCT_NAME.to_string());
+    let front_location = args
+        .front_location
+        .clone()
+        .or_else(|| profile.map(|p| p.front_location.to_string()))
+        .or_else(|| persisted.as_ref().and_then(|p| p.front_location.clone()))
+        .unwrap_or_else(|| "front".to_string());
+    let led_device_name = profile
+        .map(|p| p.led_device_name.to_string())
+        .unwrap_or_else(|| X13S_LED_DEVICE_NAME.to_string());
+    let any_camera = args.any_camera || profile.is_some_and(|p| p.any_camera);
+    let pipeline_handler = args
+        .pipeline_handler
+        .clone()
+        .or_else(|| persisted.and_then(|p| p.pipeline_handler));
+    (camera_product_name, front_location, led_device_name, any_camera, pipeline_handler)
+}
+
+fn parse_args() -> Args {
+    let mut args = Args {
+        any_camera: false,
+        watch: false,
+        off_on_exit: OffOnExit::LeaveAsIs,
+        shutdown_indicator: ShutdownIndicator::None,
+        pipewire_remote: std::env::var("PIPEWIRE_REMOTE").ok(),
+        exclude_ir: false,
+        trace_registry: false,
+        trace_state_machine: false,
+        duty: None,
+        dump_node: None,
+        smooth_suspend: false,
+        min_write_interval: None,
+        health_socket: None,
+        ignore_nodes: Vec::new(),
+        pin_object_path: None,
+        expect_camera_within: None,
+        strict: false,
+        print_config: false,
+        check_session: false,
+        brightness_percentage: false,
+        long_session_warn: None,
+        aux_leds: Vec::new(),
+        explain: false,
+        persist_error_status: false,
+        notify_fallback: None,
+        app_patterns: HashMap::new(),
+        simulate: None,
+        exclude_roles: Vec::new(),
+        use_kernel_trigger: false,
+        state_brightness: HashMap::new(),
+        led_devices: Vec::new(),
+        max_nodes: 10_000,
+        prune_excess_nodes: false,
+        early_on: false,
+        color_log: env_logger::WriteStyle::Auto,
+        replay: None,
+        replay_states: None,
+        standby_brightness: None,
+        only_my_nodes: false,
+        session_scope: session_scope::SessionScope::Any,
+        app_allowlist: Vec::new(),
+        profile: None,
+        product_name: None,
+        front_location: None,
+        pipeline_handler: None,
+        device_api: None,
+        device_serial: None,
+        on_node_error: NodeErrorAction::Log,
+        completions: None,
+        state_file: None,
+        startup_quiet: None,
+        force_state_file: None,
+        event_csv: None,
+        history_size: 50,
+        sound_on: None,
+        sound_off: None,
+        match_threshold: None,
+        match_weights: HashMap::new(),
+        debug_probe_without_stream: false,
+        require_format: false,
+        verify_write: false,
+        max_event_latency_warn: None,
+        screencast_led: None,
+        screencast_notify: false,
+        screencast_debounce: None,
+        audio_led: None,
+        audio_notify: false,
+        audio_debounce: None,
+        camera_notify: false,
+        ir_lighting_policy: IrLightingPolicy::Either,
+        notify_sandboxed: false,
+        rediscover_interval: None,
+        startup_delay: None,
+        pulse: false,
+        pulse_curve: None,
+        pulse_period: Duration::from_millis(2000),
+        als_scale: None,
+        count: None,
+        led_command: None,
+        led_command_max: 1,
+        fallback_led_device: None,
+        only_when_unlocked: false,
+        latch_clear: None,
+        dbus_timeout: None,
+        gpio_chip: None,
+        gpio_line: None,
+        cluster_listen: None,
+        cluster_peers: Vec::new(),
+        status: false,
+        status_json: false,
+    };
+    let mut rest = std::env::args().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--any-camera" => args.any_camera = true,
+            "--watch" => args.watch = true,
+            "--off-on-exit=if-we-turned-it-on" => {
+                args.off_on_exit = OffOnExit::IfWeTurnedItOn
+            }
+            "--shutdown-indicator" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--shutdown-indicator requires a value (off/blink)");
+                    std::process::exit(1);
+                });
+                args.shutdown_indicator = match value.as_str() {
+                    "off" => ShutdownIndicator::Off,
+                    "blink" => ShutdownIndicator::Blink,
+                    other => {
+                        eprintln!(
+                            "--shutdown-indicator: unknown value {:?}, expected off/blink",
+                            other
+                        );
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--pipewire-remote" => {
+                args.pipewire_remote = Some(rest.next().unwrap_or_else(|| {
+                    eprintln!("--pipewire-remote requires a value");
+                    std::process::exit(1);
+                }));
+            }
+            "--include-ir" => args.exclude_ir = false,
+            "--exclude-ir" => args.exclude_ir = true,
+            "--trace-registry" => args.trace_registry = true,
+            "--trace-state-machine" => args.trace_state_machine = true,
+            "--smooth-suspend" => args.smooth_suspend = true,
+            "--min-write-interval" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--min-write-interval requires a value (milliseconds)");
+                    std::process::exit(1);
+                });
+                args.min_write_interval = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--min-write-interval must be an integer number of milliseconds");
+                    std::process::exit(1);
+                }));
+            }
+            "--duty" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--duty requires a value");
+                    std::process::exit(1);
+                });
+                args.duty = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--duty must be an integer 0-100");
+                    std::process::exit(1);
+                }));
+            }
+            "--ignore-node" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--ignore-node requires a value (id or name)");
+                    std::process::exit(1);
+                });
+                args.ignore_nodes.push(value);
+            }
+            "--expect-camera-within" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--expect-camera-within requires a value (e.g. 10s)");
+                    std::process::exit(1);
+                });
+                args.expect_camera_within = Some(parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--expect-camera-within: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--strict" => args.strict = true,
+            "--pin-object-path" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--pin-object-path requires a value");
+                    std::process::exit(1);
+                });
+                args.pin_object_path = Some(value);
+            }
+            "--print-config" => args.print_config = true,
+            "--check-session" => args.check_session = true,
+            "--brightness-percentage" => args.brightness_percentage = true,
+            "--long-session-warn" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--long-session-warn requires a value (e.g. 2h)");
+                    std::process::exit(1);
+                });
+                args.long_session_warn = Some(parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--long-session-warn: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--health-socket" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--health-socket requires a value (path)");
+                    std::process::exit(1);
+                });
+                args.health_socket = Some(systemd::resolve_runtime_path(&value));
+            }
+            "--explain" => args.explain = true,
+            "--persist-error-status" => args.persist_error_status = true,
+            "--notify-fallback" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--notify-fallback requires a value (journal/wall)");
+                    std::process::exit(1);
+                });
+                args.notify_fallback = Some(notify_fallback::parse(&value).unwrap_or_else(|err| {
+                    eprintln!("--notify-fallback: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--use-kernel-trigger" => args.use_kernel_trigger = true,
+            "--state-brightness" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--state-brightness requires a value (state=brightness)");
+                    std::process::exit(1);
+                });
+                let (state, brightness) = value.split_once('=').unwrap_or_else(|| {
+                    eprintln!("--state-brightness must be state=brightness, got {:?}", value);
+                    std::process::exit(1);
+                });
+                let brightness: u32 = brightness.parse().unwrap_or_else(|_| {
+                    eprintln!("--state-brightness: brightness must be an integer");
+                    std::process::exit(1);
+                });
+                args.state_brightness.insert(state.to_string(), brightness);
+            }
+            "--led-device" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--led-device requires a value (device name)");
+                    std::process::exit(1);
+                });
+                args.led_devices.push(value);
+            }
+            "--max-nodes" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--max-nodes requires a value");
+                    std::process::exit(1);
+                });
+                args.max_nodes = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--max-nodes: value must be an integer");
+                    std::process::exit(1);
+                });
+            }
+            "--prune-excess-nodes" => args.prune_excess_nodes = true,
+            "--early-on" => args.early_on = true,
+            "--color-log" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--color-log requires a value (auto/always/never)");
+                    std::process::exit(1);
+                });
+                args.color_log = match value.as_str() {
+                    "auto" => env_logger::WriteStyle::Auto,
+                    "always" => env_logger::WriteStyle::Always,
+                    "never" => env_logger::WriteStyle::Never,
+                    other => {
+                        eprintln!("--color-log: unknown value {:?}, expected auto/always/never", other);
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--replay" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--replay requires a value (path to a pw-dump JSON file)");
+                    std::process::exit(1);
+                });
+                args.replay = Some(std::path::PathBuf::from(value));
+            }
+            "--replay-states" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!(
+                        "--replay-states requires a value (path to a '<id> <state> [key=value...]' script)"
+                    );
+                    std::process::exit(1);
+                });
+                args.replay_states = Some(std::path::PathBuf::from(value));
+            }
+            "--standby-brightness" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--standby-brightness requires a value");
+                    std::process::exit(1);
+                });
+                args.standby_brightness = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--standby-brightness: value must be an integer");
+                    std::process::exit(1);
+                }));
+            }
+            "--only-my-nodes" => args.only_my_nodes = true,
+            "--session-scope" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--session-scope requires a value (foreground/any/seat)");
+                    std::process::exit(1);
+                });
+                args.session_scope = session_scope::parse(&value).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                });
+            }
+            "--app-allow" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--app-allow requires a value (application.name)");
+                    std::process::exit(1);
+                });
+                args.app_allowlist.push(value);
+            }
+            "--profile" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--profile requires a value");
+                    std::process::exit(1);
+                });
+                if config::profile(&value).is_none() {
+                    let known: Vec<&str> =
+                        config::PROFILES.iter().map(|(name, _)| *name).collect();
+                    eprintln!(
+                        "--profile: unknown profile {:?}, expected one of: {}",
+                        value,
+                        known.join(", ")
+                    );
+                    std::process::exit(1);
+                }
+                args.profile = Some(value);
+            }
+            "--product-name" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--product-name requires a value (device.product.name)");
+                    std::process::exit(1);
+                });
+                args.product_name = Some(value);
+            }
+            "--front-location" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--front-location requires a value (api.libcamera.location)");
+                    std::process::exit(1);
+                });
+                args.front_location = Some(value);
+            }
+            "--pipeline-handler" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--pipeline-handler requires a value (api.libcamera.PipelineHandler)");
+                    std::process::exit(1);
+                });
+                args.pipeline_handler = Some(value);
+            }
+            "--device-api" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--device-api requires a value (device.api, e.g. libcamera or v4l2)");
+                    std::process::exit(1);
+                });
+                args.device_api = Some(value);
+            }
+            "--device-serial" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!(
+                        "--device-serial requires a value (device.serial or api.v4l2.cap.bus_info)"
+                    );
+                    std::process::exit(1);
+                });
+                args.device_serial = Some(value);
+            }
+            "--on-node-error" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--on-node-error requires a value (log/led-off/notify)");
+                    std::process::exit(1);
+                });
+                args.on_node_error = match value.as_str() {
+                    "log" => NodeErrorAction::Log,
+                    "led-off" => NodeErrorAction::LedOff,
+                    "notify" => NodeErrorAction::Notify,
+                    other => {
+                        eprintln!("--on-node-error: unknown value {:?}, expected log/led-off/notify", other);
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--completions" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!(
+                        "--completions requires a value ({})",
+                        completions::SHELLS.join("/")
+                    );
+                    std::process::exit(1);
+                });
+                if !completions::SHELLS.contains(&value.as_str()) {
+                    eprintln!(
+                        "--completions: unknown shell {:?}, expected {}",
+                        value,
+                        completions::SHELLS.join("/")
+                    );
+                    std::process::exit(1);
+                }
+                args.completions = Some(value);
+            }
+            "--state-file" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--state-file requires a path");
+                    std::process::exit(1);
+                });
+                args.state_file = Some(systemd::resolve_runtime_path(&value).to_string_lossy().into_owned());
+            }
+            "--startup-quiet" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--startup-quiet requires a value (e.g. 10s)");
+                    std::process::exit(1);
+                });
+                args.startup_quiet = Some(parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--startup-quiet: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--force-state-file" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--force-state-file requires a path");
+                    std::process::exit(1);
+                });
+                args.force_state_file = Some(systemd::resolve_runtime_path(&value).to_string_lossy().into_owned());
+            }
+            "--event-csv" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--event-csv requires a path");
+                    std::process::exit(1);
+                });
+                args.event_csv = Some(systemd::resolve_runtime_path(&value).to_string_lossy().into_owned());
+            }
+            "--history-size" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--history-size requires a number of records");
+                    std::process::exit(1);
+                });
+                args.history_size = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--history-size: invalid number: {:?}", value);
+                    std::process::exit(1);
+                });
+            }
+            "--sound-on" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--sound-on requires a path to a sound file");
+                    std::process::exit(1);
+                });
+                args.sound_on = Some(value);
+            }
+            "--sound-off" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--sound-off requires a path to a sound file");
+                    std::process::exit(1);
+                });
+                args.sound_off = Some(value);
+            }
+            "--match-threshold" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--match-threshold requires a value");
+                    std::process::exit(1);
+                });
+                args.match_threshold = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--match-threshold: value must be a number");
+                    std::process::exit(1);
+                }));
+            }
+            "--match-weight" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--match-weight requires a value (predicate=weight)");
+                    std::process::exit(1);
+                });
+                let (predicate, weight) = value.split_once('=').unwrap_or_else(|| {
+                    eprintln!("--match-weight must be predicate=weight, got {:?}", value);
+                    std::process::exit(1);
+                });
+                let weight: f64 = weight.parse().unwrap_or_else(|_| {
+                    eprintln!("--match-weight: weight must be a number");
+                    std::process::exit(1);
+                });
+                args.match_weights.insert(predicate.to_string(), weight);
+            }
+            "--debug-probe-without-stream" => args.debug_probe_without_stream = true,
+            "--require-format" => args.require_format = true,
+            "--verify-write" => args.verify_write = true,
+            "--max-event-latency-warn" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--max-event-latency-warn requires a value (milliseconds)");
+                    std::process::exit(1);
+                });
+                let millis: u64 = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--max-event-latency-warn must be an integer number of milliseconds");
+                    std::process::exit(1);
+                });
+                args.max_event_latency_warn = Some(Duration::from_millis(millis));
+            }
+            "--screencast-led" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--screencast-led requires a device name");
+                    std::process::exit(1);
+                });
+                args.screencast_led = Some(value);
+            }
+            "--screencast-notify" => args.screencast_notify = true,
+            "--screencast-debounce" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--screencast-debounce requires a value (e.g. 2s)");
+                    std::process::exit(1);
+                });
+                args.screencast_debounce = Some(parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--screencast-debounce: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--audio-led" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--audio-led requires a device name");
+                    std::process::exit(1);
+                });
+                args.audio_led = Some(value);
+            }
+            "--audio-notify" => args.audio_notify = true,
+            "--audio-debounce" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--audio-debounce requires a value (e.g. 2s)");
+                    std::process::exit(1);
+                });
+                args.audio_debounce = Some(parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--audio-debounce: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--camera-notify" => args.camera_notify = true,
+            "--ir-lighting-policy" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!(
+                        "--ir-lighting-policy requires a value (either/rgb-only/require-rgb)"
+                    );
+                    std::process::exit(1);
+                });
+                args.ir_lighting_policy = match value.as_str() {
+                    "either" => IrLightingPolicy::Either,
+                    "rgb-only" => IrLightingPolicy::RgbOnly,
+                    "require-rgb" => IrLightingPolicy::RequireRgb,
+                    other => {
+                        eprintln!(
+                            "--ir-lighting-policy: unknown value {:?}, expected either/rgb-only/require-rgb",
+                            other
+                        );
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--notify-sandboxed" => args.notify_sandboxed = true,
+            "--rediscover-interval" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--rediscover-interval requires a value (e.g. 5m)");
+                    std::process::exit(1);
+                });
+                args.rediscover_interval = Some(parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--rediscover-interval: {}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--startup-delay" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--startup-delay requires a value (milliseconds)");
+                    std::process::exit(1);
+                });
+                let ms: u64 = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--startup-delay must be an integer number of milliseconds");
+                    std::process::exit(1);
+                });
+                args.startup_delay = Some(Duration::from_millis(ms));
+            }
+            "--pulse" => args.pulse = true,
+            "--pulse-curve" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--pulse-curve requires a value (sine/triangle/keyframes:<levels>)");
+                    std::process::exit(1);
+                });
+                // Validated eagerly so a typo fails at startup rather than
+                // silently falling back once `--pulse` actually starts
+                // rendering, same as `--state-brightness`/`--match-weight`.
+                if let Err(err) = pulse::parse_curve(&value) {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+                args.pulse_curve = Some(value);
+            }
+            "--pulse-period" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--pulse-period requires a value (milliseconds)");
+                    std::process::exit(1);
+                });
+                let ms: u64 = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--pulse-period must be an integer number of milliseconds");
+                    std::process::exit(1);
+                });
+                args.pulse_period = Duration::from_millis(ms);
+            }
+            "--als-scale" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--als-scale requires a value (<min>:<max>)");
+                    std::process::exit(1);
+                });
+                args.als_scale = Some(als::parse_scale(&value).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--count" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--count requires a value (number of state transitions)");
+                    std::process::exit(1);
+                });
+                args.count = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--count must be an integer");
+                    std::process::exit(1);
+                }));
+            }
+            "--led-command" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--led-command requires a value, with {{brightness}} as a placeholder");
+                    std::process::exit(1);
+                });
+                args.led_command = Some(value);
+            }
+            "--led-command-max" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--led-command-max requires a value");
+                    std::process::exit(1);
+                });
+                args.led_command_max = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--led-command-max must be an integer");
+                    std::process::exit(1);
+                });
+            }
+            "--fallback-led-device" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--fallback-led-device requires a device name");
+                    std::process::exit(1);
+                });
+                args.fallback_led_device = Some(value);
+            }
+            "--only-when-unlocked" => args.only_when_unlocked = true,
+            "--latch" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--latch requires a value (<duration>/login, e.g. 10m)");
+                    std::process::exit(1);
+                });
+                args.latch_clear = Some(latch::parse(&value).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }));
+            }
+            "--dbus-timeout" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--dbus-timeout requires a value (milliseconds)");
+                    std::process::exit(1);
+                });
+                let ms: u64 = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--dbus-timeout must be an integer number of milliseconds");
+                    std::process::exit(1);
+                });
+                args.dbus_timeout = Some(Duration::from_millis(ms));
+            }
+            "--gpio-chip" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--gpio-chip requires a value (e.g. /sys/class/gpio/gpiochip0)");
+                    std::process::exit(1);
+                });
+                args.gpio_chip = Some(value);
+            }
+            "--gpio-line" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--gpio-line requires a value");
+                    std::process::exit(1);
+                });
+                args.gpio_line = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--gpio-line must be an integer");
+                    std::process::exit(1);
+                }));
+            }
+            "--cluster-listen" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--cluster-listen requires a value (host:port)");
+                    std::process::exit(1);
+                });
+                args.cluster_listen = Some(value);
+            }
+            "--cluster-peer" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--cluster-peer requires a value (host:port)");
+                    std::process::exit(1);
+                });
+                args.cluster_peers.push(value);
+            }
+            "--status" => args.status = true,
+            "--json" => args.status_json = true,
+            "--exclude-role" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--exclude-role requires a value (media.role or media.class)");
+                    std::process::exit(1);
+                });
+                args.exclude_roles.push(value);
+            }
+            "--simulate" => {
+                args.simulate = Some((Duration::from_secs(5), Duration::from_secs(5)));
+            }
+            "--simulate-on" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--simulate-on requires a value (e.g. 10s)");
+                    std::process::exit(1);
+                });
+                let on = parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--simulate-on: {}", err);
+                    std::process::exit(1);
+                });
+                let off = args.simulate.map(|(_, off)| off).unwrap_or(Duration::from_secs(5));
+                args.simulate = Some((on, off));
+            }
+            "--simulate-off" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--simulate-off requires a value (e.g. 10s)");
+                    std::process::exit(1);
+                });
+                let off = parse_duration(&value).unwrap_or_else(|err| {
+                    eprintln!("--simulate-off: {}", err);
+                    std::process::exit(1);
+                });
+                let on = args.simulate.map(|(on, _)| on).unwrap_or(Duration::from_secs(5));
+                args.simulate = Some((on, off));
+            }
+            "--app-pattern" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--app-pattern requires a value (application.name=duty)");
+                    std::process::exit(1);
+                });
+                let (app, duty) = value.split_once('=').unwrap_or_else(|| {
+                    eprintln!("--app-pattern must be application.name=duty, got {:?}", value);
+                    std::process::exit(1);
+                });
+                let duty: u8 = duty.parse().unwrap_or_else(|_| {
+                    eprintln!("--app-pattern: duty must be an integer 0-100");
+                    std::process::exit(1);
+                });
+                args.app_patterns.insert(app.to_string(), duty);
+            }
+            "--aux-led" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--aux-led requires a value (device[:on[:off[:invert]]])");
+                    std::process::exit(1);
+                });
+                args.aux_leds.push(value);
+            }
+            "--dump-node" => {
+                let value = rest.next().unwrap_or_else(|| {
+                    eprintln!("--dump-node requires a value");
+                    std::process::exit(1);
+                });
+                args.dump_node = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("--dump-node must be a node id");
+                    std::process::exit(1);
+                }));
+            }
+            other => {
+                eprintln!("unknown argument: {}", other);
+                std::process::exit(1);
+            }
+        }
+    }
+    args
+}
 
+/// Currently tracked node count, the one metric this guard exposes — no
+/// metrics feature/exporter exists in this tree yet (see `writer.rs`'s
+/// `MAX_WRITE_NANOS` for the same caveat), so it's surfaced through the
+/// log rather than an actual counter someone could scrape.
+static TRACKED_NODE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Bookkeeping for nodes we've bound a listener to. Keyed by proxy id in
+/// `HashMap`s so add/remove stay O(1) even under the rapid node churn a
+/// screen-sharing session can produce; this was a deliberate choice over
+/// a `Vec` scan specifically so that churn doesn't become O(n^2) as the
+/// number of concurrently-live nodes grows.
 struct Nodes {
     nodes_t: HashMap<u32, Node>,
     listeners: HashMap<u32, Vec<Box<dyn Listener>>>,
+    /// Insertion order, oldest first, so `--prune-excess-nodes` has a
+    /// well-defined "oldest" entry to evict without the cost of a second
+    /// ordered structure keyed any other way.
+    insertion_order: VecDeque<u32>,
 }
 
 impl Nodes {
@@ -27,8 +1672,19 @@ impl Nodes {
         Self {
             nodes_t: HashMap::new(),
             listeners: HashMap::new(),
+            insertion_order: VecDeque::new(),
         }
     }
+    fn len(&self) -> usize {
+        self.nodes_t.len()
+    }
+    /// Number of node ids with at least one registered listener. For
+    /// leak diagnosis alongside `len()`/`TRACKED_NODE_COUNT`: a node
+    /// removed from `nodes_t` but left in `listeners` (or vice versa)
+    /// would show up as these two drifting apart over time.
+    fn listener_count(&self) -> usize {
+        self.listeners.len()
+    }
     fn add_node_t(&mut self, node_t: Node, listener: NodeListener) {
         let proxy_id = {
             let proxy = node_t.upcast_ref();
@@ -36,6 +1692,8 @@ impl Nodes {
         };
 
         self.nodes_t.insert(proxy_id, node_t);
+        self.insertion_order.push_back(proxy_id);
+        TRACKED_NODE_COUNT.store(self.nodes_t.len(), Ordering::Relaxed);
 
         let v = self.listeners.entry(proxy_id).or_default();
         v.push(Box::new(listener));
@@ -47,104 +1705,1582 @@ impl Nodes {
     fn remove(&mut self, proxy_id: u32) {
         self.nodes_t.remove(&proxy_id);
         self.listeners.remove(&proxy_id);
+        TRACKED_NODE_COUNT.store(self.nodes_t.len(), Ordering::Relaxed);
+    }
+
+    /// Evict the oldest tracked node that isn't `keep_id` (the identified
+    /// camera, which must never be pruned), for `--prune-excess-nodes`.
+    /// Returns the evicted id, if any.
+    fn prune_oldest(&mut self, keep_id: Option<u32>) -> Option<u32> {
+        let position = self
+            .insertion_order
+            .iter()
+            .position(|id| self.nodes_t.contains_key(id) && Some(*id) != keep_id)?;
+        let proxy_id = self.insertion_order.remove(position)?;
+        self.remove(proxy_id);
+        Some(proxy_id)
     }
 }
 
-fn monitor() -> anyhow::Result<()> {
+fn monitor(args: &Args) -> anyhow::Result<()> {
+    let (camera_product_name, front_location, led_device_name_default, any_camera, pipeline_handler) =
+        resolve_identification(args);
+    let explain = args.explain;
+    let pin_object_path: Rc<Option<String>> = Rc::new(args.pin_object_path.clone());
+    let trace_registry = args.trace_registry;
+    let trace_state_machine = args.trace_state_machine;
+    let max_nodes = args.max_nodes;
+    let prune_excess_nodes = args.prune_excess_nodes;
+    let only_my_nodes = args.only_my_nodes;
+    let own_uid = current_uid();
+    if args.only_my_nodes && own_uid.is_none() {
+        log::warn!("--only-my-nodes: couldn't determine our own uid, the check will never exclude anything");
+    }
+    let software_pwm = args.duty.is_some() || !args.app_patterns.is_empty();
+    let app_patterns: Rc<HashMap<String, u8>> = Rc::new(args.app_patterns.clone());
+    let app_allowlist: Rc<Vec<String>> = Rc::new(args.app_allowlist.clone());
+    let default_duty = args.duty.unwrap_or(100);
+    let cfg = Rc::new(config::Config {
+        camera_product_name,
+        front_location,
+        led_device_name: led_device_name_default,
+        exclude_ir: args.exclude_ir,
+        brightness_map: build_brightness_map(&args.state_brightness, args.early_on, args.standby_brightness),
+        match_weights: build_match_weights(&args.match_weights),
+        match_threshold: args.match_threshold,
+        pipeline_handler,
+        device_api: args.device_api.clone(),
+        device_serial: args.device_serial.clone(),
+        ..config::Config::default()
+    });
+    let watch: Option<Rc<RefCell<watch::Watch>>> = if args.watch {
+        Some(Rc::new(RefCell::new(watch::Watch::new())))
+    } else {
+        None
+    };
+    // The only strong reference to this `Rc` lives in this local variable;
+    // every other capture of it below (`result_weak`, at both its
+    // definition sites) is a `Weak`, upgraded only for the duration of
+    // the closure call that needs it and never stored anywhere longer-
+    // lived. That invariant is what makes `Rc::into_inner(result)` at the
+    // bottom of this function safe to treat as infallible-in-practice
+    // (the `.context(...)` below is a backstop, not an expected path) —
+    // audited after a request raised it as a concern; every addition to
+    // this function since has kept following the same weak-capture
+    // pattern (`main_loop_weak`, `registry_weak`, `nodes_weak`, etc.) for
+    // exactly this reason, so there was nothing to fix here. A channel in
+    // place of this `Rc<RefCell<...>>` was also suggested as an
+    // alternative that would sidestep the leak-detection question
+    // entirely, but it would mean the several places that currently just
+    // set `*result.borrow_mut() = Err(...)` and keep running would
+    // instead need to send-and-remember, or poll a channel they don't
+    // otherwise need — more machinery for the same guarantee this
+    // invariant already gives for free.
     let result = Rc::new(RefCell::new(Ok(())));
+
+    // State for the end-of-run shutdown report (see its construction near
+    // the bottom of this function, after `main_loop.run()` returns): how
+    // we got here, how long we ran, and a few running counters that would
+    // otherwise only be visible scattered across the log.
+    let started_at = std::time::Instant::now();
+    let shutdown_reason: Rc<Cell<&'static str>> = Rc::new(Cell::new("unknown"));
+    let activation_count: Rc<Cell<u64>> = Rc::new(Cell::new(0));
+    let last_led_brightness: Rc<Cell<u32>> = Rc::new(Cell::new(X13S_LED_BRIGHTNESS_OFF));
+
+    // `--sound-on`/`--sound-off`: optional audible cues played (see
+    // `sound.rs`) when the LED's overall on/off state actually changes,
+    // i.e. at the same transition `led_turned_on_by_us` already tracks.
+    let sound_on = Rc::new(args.sound_on.clone());
+    let sound_off = Rc::new(args.sound_off.clone());
+
+    // `--state-file`: written whenever a node newly becomes the matched
+    // front camera (see the save below), read once at startup by
+    // `resolve_identification` to pre-seed identification on the next run.
+    let state_file = Rc::new(args.state_file.clone());
+
+    // `--debug-probe-without-stream`: per-node bookkeeping to detect a
+    // tracked node going `Creating` straight to `Idle`/`Suspended`
+    // without ever reaching `Running` (see the detection site below).
+    // `raw_camera_states` mirrors `camera_states` but holds the
+    // `--app-allow`-unfiltered state, since the pattern being detected is
+    // about the underlying PipeWire node, not the app-allowlist-derived
+    // LED decision.
+    let debug_probe_without_stream = args.debug_probe_without_stream;
+    let raw_camera_states: Rc<RefCell<HashMap<u32, rules::CameraState>>> =
+        Rc::new(RefCell::new(HashMap::new()));
+    let ever_active_nodes: Rc<RefCell<HashMap<u32, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+    let probe_without_stream_count: Rc<Cell<u64>> = Rc::new(Cell::new(0));
+
+    // `--require-format`: whether each node currently has a negotiated
+    // `Format` param (as opposed to merely being `Running`), kept
+    // up to date by the node's `.param()` listener set up alongside its
+    // `.info()` listener below. Only consulted when `require_format` is
+    // set; otherwise nothing ever populates or reads it.
+    let require_format = args.require_format;
+    let node_has_format: Rc<RefCell<HashMap<u32, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+
     let main_loop = pipewire::main_loop::MainLoop::new(None)?;
 
+    // Shutdown contract: SIGINT/SIGTERM call `main_loop.quit()`, which
+    // unblocks `main_loop.run()` below and falls through to the
+    // off-on-exit write and the final `result` unwrap — the same path an
+    // internal core error takes via `result_weak`/`main_loop_weak` in the
+    // core listener below, except that path leaves an `Err` in `result`
+    // first. A signal-initiated exit never touches `result`, so it stays
+    // `Ok(())` and that's what `monitor()` returns.
+    //
+    // There's no automated test exercising this: asserting it end-to-end
+    // means driving a real (or mocked) PipeWire main loop for one
+    // iteration, sending it a signal, and observing `monitor()`'s return
+    // value — which needs the loop/core to be injectable, and nothing in
+    // this tree currently separates "the loop" from "a live PipeWire
+    // connection" enough to swap in a fake one. Worth revisiting if a
+    // mock transport (see `rules::PropLookup`'s offline-tooling impl for
+    // the analogous idea on the identification side) is ever added.
     let main_loop_weak = main_loop.downgrade();
+    let shutdown_reason_for_sig = shutdown_reason.clone();
     let _sig_int = main_loop.loop_().add_signal_local(Signal::SIGINT, move || {
+        shutdown_reason_for_sig.set("SIGINT");
         if let Some(main_loop) = main_loop_weak.upgrade() {
             main_loop.quit();
         }
     });
 
     let main_loop_weak = main_loop.downgrade();
+    let shutdown_reason_for_sig = shutdown_reason.clone();
     let _sig_term = main_loop
         .loop_()
         .add_signal_local(Signal::SIGTERM, move || {
+            shutdown_reason_for_sig.set("SIGTERM");
             if let Some(main_loop) = main_loop_weak.upgrade() {
                 main_loop.quit();
             }
         });
 
+    if let Some(delay) = args.startup_delay {
+        log::info!("--startup-delay: sleeping {:?} before connecting to PipeWire", delay);
+        std::thread::sleep(delay);
+    }
+
+    // The LED backend is set up before connecting to PipeWire (rather than
+    // after, as originally written) so `camera_id`/`led_writer` below are
+    // both available to the core's `.error()` listener for `--on-node-error`,
+    // which needs to act on a tracked node's LED immediately rather than
+    // waiting for a later `info` event to reconcile it.
+    let led_device_candidates: Vec<String> = if args.led_devices.is_empty() {
+        vec![cfg.led_device_name.clone()]
+    } else {
+        args.led_devices.clone()
+    };
+    let led_device_name = match led::select_device(&led_device_candidates) {
+        Some(device_name) => {
+            log::info!("using LED device {:?}", device_name);
+            device_name
+        }
+        None if args.strict => {
+            anyhow::bail!(
+                "none of the candidate LED devices are controllable: {:?}",
+                led_device_candidates
+            );
+        }
+        None => {
+            let device_name = led_device_candidates[0].clone();
+            log::warn!(
+                "none of the candidate LED devices responded to a controllability probe; \
+                 proceeding with {:?} anyway",
+                device_name
+            );
+            device_name
+        }
+    };
+    let backend: Box<dyn LedBackend> = if let Some(command_template) = &args.led_command {
+        Box::new(led::CommandBackend::new(command_template.clone(), args.led_command_max))
+    } else if let Some(chip) = &args.gpio_chip {
+        #[cfg(feature = "gpio")]
+        {
+            Box::new(led::GpioBackend::new(chip, args.gpio_line.unwrap_or(0))?)
+        }
+        #[cfg(not(feature = "gpio"))]
+        {
+            anyhow::bail!(
+                "--gpio-chip {} was given but this binary wasn't built with the gpio feature",
+                chip
+            );
+        }
+    } else {
+        Box::new(led::LogindBackend::with_candidates(
+            led_device_candidates.clone(),
+            args.brightness_percentage,
+        ))
+    };
+    // `--fallback-led-device`: cascade to a second `LogindBackend` if the
+    // primary backend above fails a write, see `led::FallbackLedBackend`.
+    let backend: Box<dyn LedBackend> = if let Some(fallback_device) = &args.fallback_led_device {
+        Box::new(led::FallbackLedBackend::new(vec![
+            backend,
+            Box::new(led::LogindBackend::new(fallback_device.clone())),
+        ]))
+    } else {
+        backend
+    };
+    // All backend I/O (D-Bus or sysfs) runs on a dedicated writer thread
+    // so a slow write never blocks this main loop; see `writer.rs`.
+    let led_writer = writer::LedWriter::spawn(
+        backend,
+        args.persist_error_status,
+        args.verify_write,
+        args.max_event_latency_warn,
+        args.notify_fallback,
+    );
+    // Whether our own last write *requested* turning the LED on, used by
+    // `--off-on-exit=if-we-turned-it-on` to avoid stomping on an LED
+    // state some other process set. Since writes are queued to the
+    // writer thread rather than confirmed synchronously, this reflects
+    // what we asked for, not a confirmed successful write.
+    let led_turned_on_by_us: Rc<RefCell<bool>> = Rc::new(RefCell::new(false));
+
+    let camera_id: Rc<RefCell<Option<u32>>> = Rc::new(RefCell::new(None));
+    // Each tracked camera node's current state, per `cfg.brightness_map`.
+    // Declared here (rather than further down, as originally written) so
+    // it's available to the core's `.error()` listener below, for the
+    // same `--on-node-error` reason `camera_id`/`led_writer` moved up.
+    let camera_states: Rc<RefCell<HashMap<u32, rules::CameraState>>> =
+        Rc::new(RefCell::new(HashMap::new()));
+    // Whether any `Video/Source` node has ever appeared in the registry at
+    // all, camera or not - checked once initial sync completes, see
+    // `--strict`'s use below. A PipeWire security-context restriction
+    // that hides every node from us looks identical, from this daemon's
+    // perspective, to a camera that's simply not plugged in: no `global`
+    // events ever arrive. Without this check that's silent; the LED just
+    // never lights and nothing in the log says why.
+    let any_node_seen: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+    // Parallel to `camera_states`, keyed the same way: whether each
+    // tracked id was classified as the IR (vs RGB) node at identification
+    // time, via `rules::is_ir_camera`. Kept separate rather than folded
+    // into `CameraState` itself since it's orthogonal to activity and
+    // only `--ir-lighting-policy` (`desired_brightness`) cares about it.
+    let camera_is_ir: Rc<RefCell<HashMap<u32, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+    // `--rediscover-interval`: every `Video/Source` node's `GlobalObject`,
+    // owned via `to_owned()` so it outlives the `global` event that handed
+    // it to us, keyed the same way as `camera_states`/`camera_is_ir`. Only
+    // populated when the flag is set - otherwise this just sits empty, at
+    // no cost. `registry.bind()` needs the original `GlobalObject` (there's
+    // no "rebind by bare node id" API), which is why this cache exists at
+    // all rather than just re-checking from a node id.
+    let rediscover_cache: Rc<RefCell<HashMap<u32, pipewire::registry::GlobalObject<pipewire::properties::Properties>>>> =
+        Rc::new(RefCell::new(HashMap::new()));
+
     let context = pipewire::context::Context::new(&main_loop)?;
-    let core = context.connect(None)?;
+    let connect_props = args.pipewire_remote.as_ref().map(|remote| {
+        pipewire::properties::properties! {
+            *pipewire::keys::REMOTE_NAME => remote.as_str()
+        }
+    });
+    let core = context.connect(connect_props)?;
     let main_loop_weak = main_loop.downgrade();
     let result_weak = Rc::downgrade(&result);
+    let shutdown_reason_for_error = shutdown_reason.clone();
+    let camera_id_for_error = camera_id.clone();
+    let camera_states_for_error = camera_states.clone();
+    let camera_is_ir_for_error = camera_is_ir.clone();
+    let cfg_for_error = cfg.clone();
+    let led_writer_for_error = led_writer.clone();
+    let on_node_error = args.on_node_error;
+    let ir_lighting_policy = args.ir_lighting_policy;
+    let rediscover_interval = args.rediscover_interval;
+    // Explicitly request a round-trip instead of relying on whichever
+    // `done(0, _)` happens to arrive first: the registry's initial burst of
+    // `global` events is itself async, so a `done` that merely matches
+    // `id == 0` could in principle be satisfied by some *other* sync this
+    // core issues later, not the one this daemon cares about. Matching the
+    // `seq` this call returns against what `done` reports makes "initial
+    // enumeration complete" correct regardless of event timing, rather
+    // than "probably true because nothing else calls sync() today".
+    let initial_sync_seq = core.sync(0).context("failed to request initial registry sync")?;
+    // `pending_syncs` tracks every sync we're waiting on (today, just
+    // `initial_sync_seq`) so a `done` callback is only treated as
+    // progress if its `seq` actually matches one we're still waiting
+    // for - see `sync.rs`'s module doc comment.
+    let pending_syncs = Rc::new(sync::PendingSyncs::new());
+    pending_syncs.push(initial_sync_seq);
+    let ready_for_done = Rc::new(Cell::new(false));
+    let any_node_seen_for_done = any_node_seen.clone();
+    let strict_for_done = args.strict;
+    let main_loop_weak_for_done = main_loop.downgrade();
+    let result_weak_for_done = Rc::downgrade(&result);
+    let pending_syncs_for_done = pending_syncs.clone();
     let _listener = core
         .add_listener_local()
         .info(|info| {
             log::debug!("{:#?}", info);
         })
-        .done(|id, seq| {
+        .done(move |id, seq| {
             log::debug!("{}, {:?}", id, seq);
+            // No separate startup reconciliation step is needed beyond
+            // this: PipeWire sends an `info` event carrying a node's
+            // *current* state as soon as we bind a listener to it (not a
+            // stale/assumed-off one), so a camera that's already running
+            // when this daemon (re)starts is adopted correctly via that
+            // first event rather than only being noticed on its next
+            // state change. This `done` firing is just the signal that
+            // the initial burst of those events has actually arrived,
+            // distinguishing "still enumerating" from "fully synced with
+            // no camera" for readiness purposes.
+            //
+            // `mark_done` only returns true for a `seq` we're actually
+            // waiting on via `pending_syncs` (see `sync.rs`) - an
+            // out-of-order or unrelated `done` for id 0 is ignored
+            // rather than mistaken for our sync completing.
+            if id == 0 && pending_syncs_for_done.mark_done(seq) && pending_syncs_for_done.is_empty() && !ready_for_done.get() {
+                ready_for_done.set(true);
+                log::info!("initial registry sync complete (seq {:?})", seq);
+                systemd::notify_ready();
+                // A restricted PipeWire security context (e.g. a
+                // sandboxed daemon with no camera permission granted)
+                // looks, from here, identical to "no camera plugged in
+                // yet": the registry just never sends any `global`
+                // event. Checking whether *any* node showed up at all -
+                // not just a matching camera - turns that silent failure
+                // into a diagnosable one.
+                if !any_node_seen_for_done.get() {
+                    log::warn!(
+                        "no PipeWire nodes visible after initial sync; if a camera is \
+                         connected, this is likely a PipeWire security-context permission \
+                         issue, not a missing camera"
+                    );
+                    if strict_for_done {
+                        if let Some(result) = result_weak_for_done.upgrade() {
+                            *result.borrow_mut() = Err(anyhow::anyhow!(
+                                "no PipeWire nodes visible after initial sync (--strict); \
+                                 check PipeWire security-context permissions"
+                            ));
+                        }
+                        if let Some(main_loop) = main_loop_weak_for_done.upgrade() {
+                            main_loop.quit();
+                        }
+                    }
+                }
+            }
         })
         .error(move |id, seq, res, message| {
             log::error!("error id:{} seq:{} res:{}: {}", id, seq, res, message);
             if id == 0 {
                 if let Some(main_loop) = main_loop_weak.upgrade() {
+                    shutdown_reason_for_error.set("error");
                     main_loop.quit();
                     if let Some(result) = result_weak.upgrade() {
                         *result.borrow_mut() = Err(anyhow::anyhow!("pipewire error: {}", message));
                     }
                 }
+            } else if on_node_error != NodeErrorAction::Log
+                && *camera_id_for_error.borrow() == Some(id)
+            {
+                // `--on-node-error`: only the tracked front camera is acted
+                // on here — an error on some other, untracked node is still
+                // just logged above, same as before this flag existed.
+                log::warn!(
+                    "id:{} (our tracked camera) reported an error, applying --on-node-error={:?}",
+                    id,
+                    on_node_error
+                );
+                if on_node_error == NodeErrorAction::LedOff {
+                    camera_states_for_error
+                        .borrow_mut()
+                        .insert(id, rules::CameraState::Error);
+                    let led_brightness = desired_brightness(
+                        &camera_states_for_error.borrow(),
+                        &camera_is_ir_for_error.borrow(),
+                        ir_lighting_policy,
+                        &cfg_for_error,
+                    );
+                    led_writer_for_error.request(led_brightness);
+                }
+                if on_node_error == NodeErrorAction::Notify {
+                    if let Err(err) = notification(
+                        "Camera error",
+                        &format!("id:{} reported a PipeWire error: {}", id, message),
+                    ) {
+                        log::warn!("--on-node-error=notify: failed to send notification: {:?}", err);
+                    }
+                }
             }
         })
         .register();
 
+    // Timeout fallback for the readiness sync above: if `pending_syncs`
+    // (see `sync.rs`) never empties out - a core that drops or never
+    // sends `done` for some reason - fire readiness anyway rather than
+    // blocking startup (and systemd's `notify_ready()`) forever.
+    {
+        let ready_for_done = ready_for_done.clone();
+        let pending_syncs = pending_syncs.clone();
+        let any_node_seen = any_node_seen.clone();
+        let timer = main_loop.loop_().add_timer(move |_expirations| {
+            if !ready_for_done.get() {
+                log::warn!(
+                    "initial registry sync did not complete within {:?}; \
+                     proceeding without it (pending_syncs empty: {})",
+                    sync::TIMEOUT,
+                    pending_syncs.is_empty()
+                );
+                ready_for_done.set(true);
+                systemd::notify_ready();
+                if !any_node_seen.get() {
+                    log::warn!(
+                        "no PipeWire nodes visible after sync timeout; if a camera is \
+                         connected, this is likely a PipeWire security-context permission \
+                         issue, not a missing camera"
+                    );
+                }
+            }
+        });
+        let _ = timer.update_timer(Some(sync::TIMEOUT), None);
+        std::mem::forget(timer);
+    }
+
     let registry = Rc::new(core.get_registry()?);
     let registry_weak = Rc::downgrade(&registry);
 
     let nodes = Rc::new(RefCell::new(Nodes::new()));
 
-    let camera_id: Rc<RefCell<Option<u32>>> = Rc::new(RefCell::new(None));
+    // Leak diagnosis for the "stop listening to unrelated nodes" churn
+    // concern: nodes_t and listeners should track each other 1:1 (every
+    // tracked node has exactly one listener set); logging both lets a
+    // steadily growing count - or the two drifting apart - show up in
+    // logs without needing a debugger attached.
+    {
+        let nodes = nodes.clone();
+        let timer = main_loop.loop_().add_timer(move |_expirations| {
+            let nodes = nodes.borrow();
+            log::debug!(
+                "tracked nodes: nodes_t={} listeners={}",
+                nodes.len(),
+                nodes.listener_count()
+            );
+        });
+        const NODE_COUNT_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+        let _ = timer.update_timer(Some(NODE_COUNT_REPORT_INTERVAL), Some(NODE_COUNT_REPORT_INTERVAL));
+        // Intentionally leaked, same as every other always-ticking timer
+        // in this file (e.g. `ratelimit.rs`'s flusher).
+        std::mem::forget(timer);
+    }
+
+    // Each aux LED runs its own writer thread, so a slow/broken one can't
+    // delay or interfere with the primary LED.
+    let aux_leds: Rc<Vec<aux::AuxLed>> = Rc::new(
+        args.aux_leds
+            .iter()
+            .map(|spec| {
+                aux::parse_spec(spec).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                })
+            })
+            .collect(),
+    );
+
+    // Opt-in, independent of the camera LED entirely: an optional second
+    // `LedWriter` lit while any node matching `rules::matches_screencast`
+    // is present. `screencast_nodes` tracks which node ids currently
+    // match, same shape as `camera_states` but keyed to presence rather
+    // than a richer per-state mapping, since there's no analogous
+    // Active/Inactive/Error distinction for a screencast node.
+    let screencast_writer: Option<writer::LedWriter> = args.screencast_led.as_ref().map(|device_name| {
+        writer::LedWriter::spawn(
+            Box::new(led::LogindBackend::new(device_name.clone())),
+            false,
+            false,
+            None,
+            args.notify_fallback,
+        )
+    });
+    let screencast_notify = args.screencast_notify;
+    let screencast_nodes: Rc<RefCell<HashMap<u32, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+    let screencast_active: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+    // `--screencast-debounce`: settles `any_screencast` through this
+    // before it's compared against `screencast_active`, below.
+    let screencast_debounce: Option<Rc<debounce::Debounce>> = args
+        .screencast_debounce
+        .map(|window| Rc::new(debounce::Debounce::new(window)));
+
+    // Opt-in, independent of the camera LED entirely, same shape as
+    // `screencast_writer` above: an optional second `LedWriter` lit while
+    // any node matching `rules::matches_audio_sink` is in the *running*
+    // state. `audio_nodes` tracks which node ids currently qualify.
+    let audio_writer: Option<writer::LedWriter> = args.audio_led.as_ref().map(|device_name| {
+        writer::LedWriter::spawn(
+            Box::new(led::LogindBackend::new(device_name.clone())),
+            false,
+            false,
+            None,
+            args.notify_fallback,
+        )
+    });
+    let audio_notify = args.audio_notify;
+    let audio_nodes: Rc<RefCell<HashMap<u32, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+    let audio_active: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+    // `--audio-debounce`: same settling step as `screencast_debounce` above.
+    let audio_debounce: Option<Rc<debounce::Debounce>> = args
+        .audio_debounce
+        .map(|window| Rc::new(debounce::Debounce::new(window)));
+
+    // `--notify-sandboxed`: whether any currently-running tracked camera's
+    // access was mediated by an xdg-desktop-portal (see
+    // `rules::is_sandboxed`), e.g. a Flatpak app, as opposed to a native
+    // PipeWire client. `sandboxed_nodes` tracks which running tracked node
+    // ids are currently flagged, same "presence map, any one is enough"
+    // shape as `screencast_nodes`/`screencast_active` above - there's no
+    // LED to drive here, just a notification on transition.
+    let notify_sandboxed = args.notify_sandboxed;
+    let sandboxed_nodes: Rc<RefCell<HashMap<u32, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+    let sandboxed_active: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+
+    // Which app currently holds each running tracked camera, by node id,
+    // used to pick a per-app pattern via `--app-pattern`.
+    //
+    // A continuously-updated D-Bus `ActiveClients` property listing which
+    // PIDs/binaries hold the camera (as opposed to this in-process-only
+    // map) would need an actual D-Bus *server* side: nothing in this tree
+    // exposes an object on the bus today (`led.rs`/`notification()` are
+    // purely clients of logind/the notification daemon). That's a
+    // prerequisite — a `zbus::connection::Builder` with a well-known name
+    // and an `#[interface]`-derived object, wired to emit property-change
+    // signals as this map's contents change — substantial enough to be
+    // its own change rather than a quiet addition here. `application.name`
+    // is also the only client identity tracked so far; PID isn't captured
+    // at all yet (`application.process.id` is the likely PipeWire prop),
+    // so that's a second prerequisite on top.
+    //
+    // A `Pause()`/`Resume()` D-Bus method pair on a "status interface"
+    // was requested (to stop LED control temporarily without killing the
+    // service, with a `Paused` property, described as the D-Bus
+    // counterpart to "the kill-switch file"). Both referenced pieces of
+    // infrastructure are missing: there's no D-Bus object-server side at
+    // all (same prerequisite as the `ActiveClients` idea above — a
+    // `zbus::connection::Builder`-owned well-known name plus an
+    // `#[interface]`-derived object), and there's no kill-switch file of
+    // any kind elsewhere in this tree to have a counterpart to. Once an
+    // object-server side exists for some other reason, pausing would
+    // plug in naturally as an `Rc<Cell<bool>>` checked right where
+    // `led_writer.request`/`led_writer.request_blocking` are called
+    // below, short-circuiting the write while still letting
+    // `camera_states` update underneath so resuming can reconcile
+    // immediately from current state rather than stale state.
+    //
+    // A D-Bus-activatable service (a `.service` file plus requesting a
+    // well-known name so a client can start this on demand, tying in the
+    // status interface and Pause()/Resume() above) has the same
+    // prerequisite as both: no object-server side exists in this tree at
+    // all yet. It's also a strict superset of the other two asks — it
+    // can't be done first, or independently of them, since "tie the
+    // name-ownership, status interface, and pause/resume into one
+    // service" presupposes the other two already exist to tie together.
+    // Once they do, activation itself is a small addition on top: a
+    // `zbus::connection::Builder::name(...)` call, a `.service` file
+    // pointing at this binary with `Exec=`, and a `NameLost`/disconnect
+    // handler that calls `main_loop.quit()` the same way the SIGINT/
+    // SIGTERM handlers below already do.
+    //
+    // A `monitor-dbus` subcommand to subscribe to the status interface's
+    // `StateChanged`/`PropertiesChanged` signals and tail them to stdout
+    // was requested next. Same prerequisite as the three notes above: the
+    // status interface it would subscribe to doesn't exist, since there's
+    // no object-server side anywhere in this tree yet. Once one does,
+    // this would actually be one of the easier pieces to add — a
+    // `zbus::blocking::Connection::session()` plus
+    // `MessageStream`/`receive_properties_changed` loop, no object server
+    // of its own required on this side, since it's purely a client of the
+    // (then-existing) interface the same way `led.rs`/`notification()`
+    // already are clients of logind/the notification daemon.
+    let camera_apps: Rc<RefCell<HashMap<u32, String>>> = Rc::new(RefCell::new(HashMap::new()));
+    let current_duty: Rc<Cell<u8>> = Rc::new(Cell::new(default_duty));
+
+    if args.pulse {
+        // Takes priority over `--duty`'s software PWM below: both would
+        // otherwise try to own writing the LED from their own timer.
+        let curve = pulse::parse_curve(args.pulse_curve.as_deref().unwrap_or("sine"))
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+        // `max_brightness` is read directly off sysfs here (not via
+        // `led_writer`, which only ever accepts queued writes, never a
+        // read back) to scale the curve's `0.0..=1.0` output into an
+        // actual brightness value; this is a plain fs read, not a D-Bus
+        // round-trip, so doing it on the main thread before the loop
+        // starts is fine.
+        let max_brightness = led::LogindBackend::new(led_device_name.clone())
+            .max_brightness()
+            .unwrap_or_else(|err| {
+                log::warn!(
+                    "--pulse: failed to read max_brightness, assuming 1 (on/off only): {:?}",
+                    err
+                );
+                1
+            });
+        pulse::start(
+            main_loop.loop_(),
+            led_writer.clone(),
+            camera_states.clone(),
+            curve,
+            args.pulse_period,
+            max_brightness,
+        );
+    } else if let Some(scale) = args.als_scale {
+        als::start(main_loop.loop_(), led_writer.clone(), camera_states.clone(), scale);
+    } else if software_pwm {
+        // `--app-pattern` can't retarget a kernel trigger live the way it
+        // retargets the software timer's `current_duty` cell, so the
+        // kernel trigger is only attempted when there's a single fixed
+        // pattern to offload. Falls back to the software timer whenever
+        // the trigger isn't available, or there are per-app patterns.
+        let duty = default_duty.clamp(1, 99) as u64;
+        let offloaded = args.use_kernel_trigger
+            && args.app_patterns.is_empty()
+            && kernel_trigger::is_available(&led_device_name)
+            && kernel_trigger::configure_blink(&led_device_name, duty * 20, (100 - duty) * 20)
+                .inspect_err(|err| {
+                    log::warn!(
+                        "failed to configure kernel LED trigger, falling back to software PWM: {:?}",
+                        err
+                    );
+                })
+                .is_ok();
+        if offloaded {
+            log::info!("offloaded software-PWM blink to the kernel timer trigger");
+        } else {
+            pwm::start(
+                main_loop.loop_(),
+                led_writer.clone(),
+                camera_states.clone(),
+                current_duty.clone(),
+                X13S_LED_BRIGHTNESS_ON,
+                X13S_LED_BRIGHTNESS_OFF,
+            );
+        }
+    }
+
+    let suspended = if args.smooth_suspend {
+        match suspend::watch() {
+            Ok(flag) => Some(flag),
+            Err(err) => {
+                log::error!("--smooth-suspend: failed to start watcher: {:?}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // `--latch login`'s clear condition needs the same `LockedHint`
+    // watcher `--only-when-unlocked` uses, started here too if it isn't
+    // already running for that flag.
+    let needs_session_lock_watch =
+        args.only_when_unlocked || matches!(args.latch_clear, Some(latch::LatchClear::Login));
+    let session_locked = if needs_session_lock_watch {
+        match sessionlock::watch() {
+            Ok(flag) => Some(flag),
+            Err(err) => {
+                log::error!("--only-when-unlocked/--latch login: failed to start watcher: {:?}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let latch: Option<Rc<latch::Latch>> = args.latch_clear.map(latch::Latch::new).map(Rc::new);
+
+    let in_scope_uids: Option<Arc<Mutex<std::collections::HashSet<u32>>>> =
+        if args.session_scope == session_scope::SessionScope::Any {
+            None
+        } else {
+            match session_scope::watch(args.session_scope) {
+                Ok(uids) => Some(uids),
+                Err(err) => {
+                    log::error!("--session-scope: failed to start watcher: {:?}", err);
+                    None
+                }
+            }
+        };
+
+    let cluster = if args.cluster_listen.is_some() || !args.cluster_peers.is_empty() {
+        match cluster::start(args.cluster_listen.as_deref(), &args.cluster_peers) {
+            Ok(cluster) => Some(Rc::new(cluster)),
+            Err(err) => {
+                log::error!("--cluster-listen/--cluster-peer: failed to start: {:?}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let rate_limiter: Option<Rc<ratelimit::RateLimitedWriter>> =
+        args.min_write_interval.map(|ms| {
+            let interval = Duration::from_millis(ms);
+            let rate_limited = ratelimit::RateLimitedWriter::new(led_writer.clone(), interval);
+            ratelimit::start_flusher(main_loop.loop_(), rate_limited.clone(), interval);
+            rate_limited
+        });
+
+    if let Some(expect_within) = args.expect_camera_within {
+        let camera_states = camera_states.clone();
+        let strict = args.strict;
+        let main_loop_weak = main_loop.downgrade();
+        let result_weak = Rc::downgrade(&result);
+        // One-shot: fires once, `update_timer`'s `interval` is `None`.
+        // Checking `camera_states` fresh at fire time (rather than
+        // pre-arming a cancellable check) means a camera found in the
+        // meantime naturally suppresses the warning — no explicit
+        // "reset" bookkeeping needed.
+        let timer = main_loop.loop_().add_timer(move |_expirations| {
+            if camera_states.borrow().is_empty() {
+                log::warn!(
+                    "no camera matched the identification rule within {:?}; \
+                     check --print-config and --dump-node",
+                    expect_within
+                );
+                if strict {
+                    if let Some(result) = result_weak.upgrade() {
+                        *result.borrow_mut() = Err(anyhow::anyhow!(
+                            "no camera matched within {:?} (--strict)",
+                            expect_within
+                        ));
+                    }
+                    if let Some(main_loop) = main_loop_weak.upgrade() {
+                        main_loop.quit();
+                    }
+                }
+            }
+        });
+        let _ = timer.update_timer(Some(expect_within), None);
+        std::mem::forget(timer);
+    }
+
+    if let Some(quiet) = args.startup_quiet {
+        let camera_states = camera_states.clone();
+        let camera_is_ir = camera_is_ir.clone();
+        let cfg = cfg.clone();
+        let led_writer = led_writer.clone();
+        // One-shot, fires once the quiet window ends: a camera that's been
+        // running the whole time may never have gotten a fresh `info`
+        // event to re-trigger the held-back ON write (the per-node
+        // callback above only writes when it runs), so re-apply here from
+        // whatever `camera_states` says right now rather than waiting for
+        // one. Re-derives just the brightest tracked state, not the full
+        // cluster/lock/rate-limit pipeline the per-node callback runs -
+        // those will catch up on the node's next real event; this is only
+        // about not leaving a genuinely-active camera's LED dark past the
+        // window.
+        let timer = main_loop.loop_().add_timer(move |_expirations| {
+            let led_brightness = desired_brightness(
+                &camera_states.borrow(),
+                &camera_is_ir.borrow(),
+                ir_lighting_policy,
+                &cfg,
+            );
+            if led_brightness == X13S_LED_BRIGHTNESS_ON {
+                log::info!("--startup-quiet: window elapsed, re-applying LED state");
+                led_writer.request(led_brightness);
+            }
+        });
+        let _ = timer.update_timer(Some(quiet), None);
+        std::mem::forget(timer);
+    }
+
+    // `--force-state-file`: polled on a regular timer rather than only
+    // checked where it's consumed, so an override expiring with no other
+    // PipeWire activity going on still gets logged (and the LED re-applied
+    // from `camera_states`, same one-shot-timer-at-window-end idea as
+    // `--startup-quiet` above, except recurring since this window's end
+    // isn't known up front).
+    let force = args.force_state_file.clone().map(force::Force::new).map(Rc::new);
+    if let Some(force) = &force {
+        force.poll();
+        let force = force.clone();
+        let camera_states = camera_states.clone();
+        let camera_is_ir = camera_is_ir.clone();
+        let cfg = cfg.clone();
+        let led_writer = led_writer.clone();
+        let timer = main_loop.loop_().add_timer(move |_expirations| {
+            let was_active = force.active().is_some();
+            force.poll();
+            if was_active && force.active().is_none() {
+                let led_brightness = desired_brightness(
+                    &camera_states.borrow(),
+                    &camera_is_ir.borrow(),
+                    ir_lighting_policy,
+                    &cfg,
+                );
+                led_writer.request(led_brightness);
+            }
+        });
+        let _ = timer.update_timer(Some(force::POLL_INTERVAL), Some(force::POLL_INTERVAL));
+        std::mem::forget(timer);
+    }
+
+    // `--camera-notify`: sent on each off-to-on transition below, with a
+    // "Disable camera LED" action button. `led_disabled` is a one-way
+    // kill switch, not a real pause/resume (see its doc comment on the
+    // `Args::camera_notify` field for why) - once set it stays set for
+    // the rest of this run, same spirit as `--force-state-file` wins
+    // over every other clamp, except even more so: an explicit click
+    // just now should override even a currently-active forced override.
+    // `invoked` is polled on a timer rather than acted on directly from
+    // the watcher thread since every other piece of daemon state here is
+    // `Rc`-based and only ever touched from the main loop's thread.
+    let camera_notify = args.camera_notify;
+    let led_disabled: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+    if camera_notify {
+        match notify_action::watch(notify_action::DISABLE_LED_ACTION) {
+            Ok(invoked) => {
+                let led_disabled = led_disabled.clone();
+                let led_writer = led_writer.clone();
+                let timer = main_loop.loop_().add_timer(move |_expirations| {
+                    if invoked.swap(false, Ordering::SeqCst) {
+                        log::info!("--camera-notify: \"Disable camera LED\" action invoked");
+                        led_disabled.set(true);
+                        led_writer.request(X13S_LED_BRIGHTNESS_OFF);
+                    }
+                });
+                let _ = timer.update_timer(
+                    Some(notify_action::ACTION_POLL_INTERVAL),
+                    Some(notify_action::ACTION_POLL_INTERVAL),
+                );
+                std::mem::forget(timer);
+            }
+            Err(err) => {
+                log::error!(
+                    "--camera-notify: failed to watch for notification actions: {:?}",
+                    err
+                );
+            }
+        }
+    }
+
+    // `--event-csv`: a failed open is logged and disables the flag for
+    // this run, same "degrade, don't abort" treatment as `--cluster-listen`
+    // above, rather than taking the whole daemon down over it.
+    let event_csv = args.event_csv.as_deref().and_then(|path| {
+        match event_csv::EventCsv::open(path) {
+            Ok(event_csv) => Some(Rc::new(RefCell::new(event_csv))),
+            Err(err) => {
+                log::error!("--event-csv: failed to open {:?}: {:?}", path, err);
+                None
+            }
+        }
+    });
+
+    // `--history-size`: always built (possibly with a 0 capacity, which
+    // makes every push a no-op), same unconditional-but-possibly-empty
+    // shape as `rediscover_cache` elsewhere — no separate
+    // Option/enabled flag to check at each push site.
+    let history: Rc<RefCell<history::History>> =
+        Rc::new(RefCell::new(history::History::new(args.history_size)));
+
+    let ignore_nodes: Rc<Vec<String>> = Rc::new(args.ignore_nodes.clone());
+    let exclude_roles: Rc<Vec<String>> = Rc::new(args.exclude_roles.clone());
+
+    let long_session_warn: Option<Rc<longsession::LongSessionWarn>> =
+        args.long_session_warn.map(|threshold| {
+            let warn = longsession::LongSessionWarn::new(threshold);
+            let camera_label = cfg.camera_label();
+            longsession::start(main_loop.loop_(), warn.clone(), move |elapsed| {
+                if let Err(err) = notification(
+                    i18n::messages().long_session_warn_summary,
+                    &format!("{} continuously active for {:?}", camera_label, elapsed),
+                ) {
+                    log::error!("failed to send long-session notification: {:?}", err);
+                }
+            });
+            warn
+        });
+
+    if let Some(socket_path) = &args.health_socket {
+        health::start(main_loop.loop_(), socket_path.clone())
+            .context("failed to start health-check socket")?;
+    }
+
+    // `--count`: a hidden option (not in the README) for CI smoke tests
+    // to run the real monitor against a real/mock graph, trigger a
+    // couple of transitions, and get a deterministic exit instead of
+    // having to kill the process externally. `None` (default) never
+    // quits on its own, the prior behavior.
+    let count_limit = args.count;
+    let transition_count: Rc<Cell<u64>> = Rc::new(Cell::new(0));
+    let main_loop_weak = main_loop.downgrade();
+
+    // Extra clones for `global_remove` below, so it can recompute and
+    // write the LED brightness itself when the removed node was our
+    // tracked camera, same "clone for the closure, keep the original
+    // alive for later" shape as `_for_error` above `core`'s listener.
+    let led_writer_for_remove = led_writer.clone();
+    let cfg_for_remove = cfg.clone();
+    let force_for_remove = force.clone();
+    let cluster_for_remove = cluster.clone();
+    let session_locked_for_remove = session_locked.clone();
+    let latch_for_remove = latch.clone();
+    let aux_leds_for_remove = aux_leds.clone();
+    let watch_for_remove = watch.clone();
+    let sound_on_for_remove = sound_on.clone();
+    let sound_off_for_remove = sound_off.clone();
+    let led_turned_on_by_us_for_remove = led_turned_on_by_us.clone();
+    let last_led_brightness_for_remove = last_led_brightness.clone();
+    let led_disabled_for_remove = led_disabled.clone();
+    let long_session_warn_for_remove = long_session_warn.clone();
 
     let _registry_listener = registry
         .add_listener_local()
         .global({
             let camera_id = camera_id.clone();
+            let camera_states = camera_states.clone();
+            let camera_is_ir = camera_is_ir.clone();
+            let any_node_seen = any_node_seen.clone();
+            let rediscover_cache = rediscover_cache.clone();
+            let led_writer = led_writer.clone();
+            let led_turned_on_by_us = led_turned_on_by_us.clone();
+            let watch = watch.clone();
+            let suspended = suspended.clone();
+            let session_locked = session_locked.clone();
+            let latch = latch.clone();
+            let cluster = cluster.clone();
+            let in_scope_uids = in_scope_uids.clone();
+            let rate_limiter = rate_limiter.clone();
+            let ignore_nodes = ignore_nodes.clone();
+            let exclude_roles = exclude_roles.clone();
+            let long_session_warn = long_session_warn.clone();
+            let pin_object_path = pin_object_path.clone();
+            let aux_leds = aux_leds.clone();
+            let camera_apps = camera_apps.clone();
+            let app_patterns = app_patterns.clone();
+            let app_allowlist = app_allowlist.clone();
+            let current_duty = current_duty.clone();
+            let cfg = cfg.clone();
+            let activation_count = activation_count.clone();
+            let last_led_brightness = last_led_brightness.clone();
+            let sound_on = sound_on.clone();
+            let sound_off = sound_off.clone();
+            let state_file = state_file.clone();
+            let force = force.clone();
+            let led_disabled = led_disabled.clone();
+            let event_csv = event_csv.clone();
+            let history = history.clone();
+            let raw_camera_states = raw_camera_states.clone();
+            let ever_active_nodes = ever_active_nodes.clone();
+            let probe_without_stream_count = probe_without_stream_count.clone();
+            let screencast_writer = screencast_writer.clone();
+            let screencast_nodes = screencast_nodes.clone();
+            let screencast_active = screencast_active.clone();
+            let screencast_debounce = screencast_debounce.clone();
+            let audio_writer = audio_writer.clone();
+            let audio_nodes = audio_nodes.clone();
+            let audio_active = audio_active.clone();
+            let audio_debounce = audio_debounce.clone();
+            let sandboxed_nodes = sandboxed_nodes.clone();
+            let sandboxed_active = sandboxed_active.clone();
+            let transition_count = transition_count.clone();
+            let main_loop_weak = main_loop_weak.clone();
+            let node_has_format = node_has_format.clone();
             move |obj| {
+                if trace_registry {
+                    log::info!("global id:{} type:{:?}", obj.id, obj.type_);
+                }
                 if let Some(registry) = registry_weak.upgrade() {
                     match obj.type_ {
                         ObjectType::Node => {
+                            any_node_seen.set(true);
+                            if rediscover_interval.is_some() {
+                                rediscover_cache.borrow_mut().insert(obj.id, obj.to_owned());
+                            }
                             let camera_id = camera_id.clone();
+                            let camera_states = camera_states.clone();
+                            let camera_is_ir = camera_is_ir.clone();
+                            let led_writer = led_writer.clone();
+                            let led_turned_on_by_us = led_turned_on_by_us.clone();
+                            let watch = watch.clone();
+                            let suspended = suspended.clone();
+            let session_locked = session_locked.clone();
+            let latch = latch.clone();
+            let cluster = cluster.clone();
+                            let in_scope_uids = in_scope_uids.clone();
+                            let rate_limiter = rate_limiter.clone();
+                            let ignore_nodes = ignore_nodes.clone();
+                            let exclude_roles = exclude_roles.clone();
+                            let long_session_warn = long_session_warn.clone();
+                            let pin_object_path = pin_object_path.clone();
+                            let aux_leds = aux_leds.clone();
+                            let camera_apps = camera_apps.clone();
+                            let app_patterns = app_patterns.clone();
+                            let app_allowlist = app_allowlist.clone();
+                            let current_duty = current_duty.clone();
+                            let cfg = cfg.clone();
+                            let activation_count = activation_count.clone();
+                            let last_led_brightness = last_led_brightness.clone();
+                            let sound_on = sound_on.clone();
+                            let sound_off = sound_off.clone();
+                            let state_file = state_file.clone();
+                            let force = force.clone();
+                            let led_disabled = led_disabled.clone();
+                            let event_csv = event_csv.clone();
+                            let history = history.clone();
+                            let raw_camera_states = raw_camera_states.clone();
+                            let ever_active_nodes = ever_active_nodes.clone();
+                            let probe_without_stream_count = probe_without_stream_count.clone();
+                            let screencast_writer = screencast_writer.clone();
+                            let screencast_nodes = screencast_nodes.clone();
+                            let screencast_active = screencast_active.clone();
+                            let screencast_debounce = screencast_debounce.clone();
+                            let audio_writer = audio_writer.clone();
+                            let audio_nodes = audio_nodes.clone();
+                            let audio_active = audio_active.clone();
+                            let audio_debounce = audio_debounce.clone();
+                            let sandboxed_nodes = sandboxed_nodes.clone();
+                            let sandboxed_active = sandboxed_active.clone();
+                            let transition_count = transition_count.clone();
+                            let main_loop_weak = main_loop_weak.clone();
+                            let node_has_format = node_has_format.clone();
 
-                            let node: Node = registry.bind(obj).unwrap();
+                            let node: Node = match registry.bind(obj) {
+                                Ok(node) => node,
+                                Err(err) => {
+                                    // The node can vanish between being
+                                    // advertised and us binding it (e.g. a
+                                    // short-lived probe node); that's a
+                                    // normal race, not a reason to take
+                                    // the whole daemon down.
+                                    log::debug!(
+                                        "id:{} failed to bind node, skipping: {:?}",
+                                        obj.id,
+                                        err
+                                    );
+                                    return;
+                                }
+                            };
+                            // Audited per this request: `NodeListenerLocalBuilder::register()`
+                            // (like `ProxyListenerLocalBuilder::register()` below, and every
+                            // other `add_listener_local().../register()` chain in this crate)
+                            // returns the listener handle directly, not a `Result` - there is
+                            // no failure path to handle here. `pipewire`'s own `StreamListener`
+                            // is the only `register()` in that crate that's fallible, and this
+                            // crate never constructs a `Stream`. Nothing to change; left as a
+                            // comment so a future audit doesn't re-raise the same question.
+                            // `--require-format`: ask PipeWire to emit `param` events for the
+                            // negotiated-format param id, so `node_has_format` below reflects
+                            // whether a real capture format is actually negotiated, not just
+                            // that the node exists. A no-op (no events ever fire) when
+                            // `require_format` is off, so this is unconditional rather than
+                            // gated, same as `node_has_format`'s declaration above.
+                            node.subscribe_params(&[ParamType::Format]);
+                            let node_has_format_for_param = node_has_format.clone();
+                            let node_id = obj.id;
                             let node_listener = node
                                 .add_listener_local()
+                                .param(move |_seq, id, _index, _next, pod| {
+                                    if id == ParamType::Format {
+                                        node_has_format_for_param
+                                            .borrow_mut()
+                                            .insert(node_id, pod.is_some());
+                                    }
+                                })
                                 .info(move |info| {
-                                    if let Some(props) = info.props() {
-                                        if props.get("media.role") == Some("Camera")
-                                            && props.get("api.libcamera.location") == Some("front")
-                                            && props.get("device.product.name")
-                                                == Some(X13S_CAMERA_PRODUCT_NAME)
-                                        {
-                                            log::info!("id:{} is my front camera", info.id());
-                                            camera_id.borrow_mut().replace(info.id());
+                                    if explain {
+                                        if let Some(props) = info.props() {
+                                            if props.get("media.role") == Some("Camera") {
+                                                let explanation =
+                                                    rules::explain_camera_match(props, &cfg);
+                                                log::info!(
+                                                    "id:{} explain: {} => {}",
+                                                    info.id(),
+                                                    explanation,
+                                                    explanation.matches()
+                                                );
+                                            }
+                                        }
+                                    }
+                                    // Screencast tracking is entirely independent of the
+                                    // camera-identification logic below: a node either matches
+                                    // `rules::matches_screencast` or it doesn't, with no
+                                    // per-node state mapping or app filtering, so it's handled
+                                    // up front rather than threaded through `is_tracked`.
+                                    if let Some(screencast_writer) = &screencast_writer {
+                                        let is_screencast = info
+                                            .props()
+                                            .map(rules::matches_screencast)
+                                            .unwrap_or(false);
+                                        if is_screencast {
+                                            screencast_nodes.borrow_mut().insert(info.id(), true);
+                                        } else {
+                                            screencast_nodes.borrow_mut().remove(&info.id());
+                                        }
+                                        let any_screencast = !screencast_nodes.borrow().is_empty();
+                                        let any_screencast = match &screencast_debounce {
+                                            Some(debounce) => debounce.apply(any_screencast),
+                                            None => any_screencast,
+                                        };
+                                        if any_screencast != screencast_active.get() {
+                                            screencast_active.set(any_screencast);
+                                            screencast_writer.request(if any_screencast {
+                                                X13S_LED_BRIGHTNESS_ON
+                                            } else {
+                                                X13S_LED_BRIGHTNESS_OFF
+                                            });
+                                            if screencast_notify {
+                                                let body = if any_screencast {
+                                                    "Screen capture started"
+                                                } else {
+                                                    "Screen capture stopped"
+                                                };
+                                                if let Err(err) = notification(
+                                                    i18n::messages().screencast_summary,
+                                                    body,
+                                                ) {
+                                                    log::error!(
+                                                        "failed to send screencast notification: {:?}",
+                                                        err
+                                                    );
+                                                }
+                                            }
                                         }
                                     }
-                                    if *camera_id.borrow() == Some(info.id()) {
-                                        log::info!("camera state: {:?}", info.state());
-                                        let led_brightness = match info.state() {
-                                            NodeState::Running => X13S_LED_BRIGHTNESS_ON,
-                                            _ => X13S_LED_BRIGHTNESS_OFF,
+                                    // `--audio-led`: same independent, up-front shape as the
+                                    // screencast tracking right above, except "matches" also
+                                    // requires the node actually be in the running state - an
+                                    // `Audio/Sink` node exists for every output device at all
+                                    // times, not just while something is playing through it.
+                                    if let Some(audio_writer) = &audio_writer {
+                                        let is_audio_sink = info
+                                            .props()
+                                            .map(rules::matches_audio_sink)
+                                            .unwrap_or(false);
+                                        let is_running = matches!(
+                                            rules::camera_state_from_node_state(&info.state()),
+                                            rules::CameraState::Active
+                                        );
+                                        if is_audio_sink && is_running {
+                                            audio_nodes.borrow_mut().insert(info.id(), true);
+                                        } else {
+                                            audio_nodes.borrow_mut().remove(&info.id());
+                                        }
+                                        let any_audio = !audio_nodes.borrow().is_empty();
+                                        let any_audio = match &audio_debounce {
+                                            Some(debounce) => debounce.apply(any_audio),
+                                            None => any_audio,
                                         };
-                                        log::info!("set led brightness: {}", led_brightness);
-                                        if let Err(err) = set_led_brightness(led_brightness) {
-                                            log::error!("failed to set LED brightness: {:?}", err);
-                                            if let Err(err) = notification(
-                                                "Camera state changed",
-                                                &format!("{:?}", info.state()),
-                                            ) {
-                                                log::error!(
-                                                    "failed to send notification: {:?}",
-                                                    err
+                                        if any_audio != audio_active.get() {
+                                            audio_active.set(any_audio);
+                                            audio_writer.request(if any_audio {
+                                                X13S_LED_BRIGHTNESS_ON
+                                            } else {
+                                                X13S_LED_BRIGHTNESS_OFF
+                                            });
+                                            if audio_notify {
+                                                let app = info
+                                                    .props()
+                                                    .and_then(|props| props.get("application.name"));
+                                                let body = match (any_audio, app) {
+                                                    (true, Some(app)) => {
+                                                        format!("Audio playback started ({})", app)
+                                                    }
+                                                    (true, None) => "Audio playback started".to_string(),
+                                                    (false, _) => "Audio playback stopped".to_string(),
+                                                };
+                                                if let Err(err) = notification(
+                                                    i18n::messages().audio_summary,
+                                                    &body,
+                                                ) {
+                                                    log::error!(
+                                                        "failed to send audio notification: {:?}",
+                                                        err
+                                                    );
+                                                }
+                                            }
+                                        }
+                                    }
+
+                                    let ignored = info
+                                        .props()
+                                        .map(|props| {
+                                            rules::is_ignored(info.id(), props, &ignore_nodes)
+                                                || rules::is_excluded_role(props, &exclude_roles)
+                                                || (only_my_nodes
+                                                    && own_uid
+                                                        .map(|uid| rules::is_other_users_node(props, uid))
+                                                        .unwrap_or(false))
+                                                || in_scope_uids
+                                                    .as_ref()
+                                                    .is_some_and(|in_scope| {
+                                                        rules::is_out_of_session_scope(
+                                                            props,
+                                                            &in_scope.lock().unwrap(),
+                                                        )
+                                                    })
+                                        })
+                                        .unwrap_or(false);
+                                    let mut untracked_now = false;
+                                    // Deterministic, first-match-wins precedence between the
+                                    // identification modes: `--ignore-node` always wins (a node
+                                    // on the exclude list is never tracked no matter how well it
+                                    // otherwise matches), then `--pin-object-path`, then
+                                    // `--any-camera`, then the default front-camera predicate.
+                                    // There is only one active mode at a time today since there's
+                                    // no multi-rule config yet; this ordering is what "first match
+                                    // wins" will mean once such a config exists.
+                                    let is_tracked = if ignored {
+                                        false
+                                    } else if let Some(path) = pin_object_path.as_ref() {
+                                        info.props()
+                                            .map(|props| rules::matches_object_path(props, path))
+                                            .unwrap_or(false)
+                                    } else if any_camera {
+                                        info.props()
+                                            .map(rules::matches_any_camera)
+                                            .unwrap_or(false)
+                                    } else {
+                                        // Re-run the predicate on every `info` event (not
+                                        // just the first match) so a node that stops
+                                        // matching at runtime (e.g. `api.libcamera.location`
+                                        // changing away from `front`) is un-tracked again.
+                                        // `--match-threshold`: swap the strict AND predicate
+                                        // for the scored one, which tolerates a missing prop
+                                        // as long as enough of the others still clear the
+                                        // configured threshold.
+                                        let matches = info
+                                            .props()
+                                            .map(|props| {
+                                                if cfg.match_threshold.is_some() {
+                                                    rules::matches_camera_scored(props, &cfg)
+                                                } else {
+                                                    rules::matches_camera(props, &cfg)
+                                                }
+                                            })
+                                            .unwrap_or(false);
+                                        if matches {
+                                            if *camera_id.borrow() != Some(info.id()) {
+                                                log::info!("id:{} is my front camera", info.id());
+                                                if let Some(path) = state_file.as_ref() {
+                                                    if let Some(props) = info.props() {
+                                                        state_file::save(
+                                                            path,
+                                                            &state_file::PersistedIdentity {
+                                                                camera_product_name: props
+                                                                    .get("device.product.name")
+                                                                    .map(String::from),
+                                                                front_location: props
+                                                                    .get("api.libcamera.location")
+                                                                    .map(String::from),
+                                                                pipeline_handler: props
+                                                                    .get("api.libcamera.PipelineHandler")
+                                                                    .map(String::from),
+                                                            },
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                            camera_id.borrow_mut().replace(info.id());
+                                        } else if *camera_id.borrow() == Some(info.id()) {
+                                            log::info!(
+                                                "id:{} no longer matches my front camera",
+                                                info.id()
+                                            );
+                                            *camera_id.borrow_mut() = None;
+                                            camera_states.borrow_mut().remove(&info.id());
+                                            camera_is_ir.borrow_mut().remove(&info.id());
+                                            untracked_now = true;
+                                        }
+                                        *camera_id.borrow() == Some(info.id())
+                                    };
+
+                                    // Filled in below when a tracked camera's state actually
+                                    // changes, then consumed once `led_brightness` is final
+                                    // (after every clamp) so `--event-csv` rows report the
+                                    // brightness actually applied, not an intermediate value.
+                                    let mut csv_event: Option<(u32, String, String, String)> = None;
+                                    if is_tracked || untracked_now {
+                                        if is_tracked {
+                                            log::info!("camera state: {:?}", info.state());
+                                            let raw_state = rules::camera_state_from_node_state(
+                                                &info.state(),
+                                            );
+                                            if debug_probe_without_stream {
+                                                let previous_raw_state = raw_camera_states
+                                                    .borrow_mut()
+                                                    .insert(info.id(), raw_state);
+                                                if raw_state.is_active() {
+                                                    ever_active_nodes
+                                                        .borrow_mut()
+                                                        .insert(info.id(), true);
+                                                }
+                                                let ever_active = ever_active_nodes
+                                                    .borrow()
+                                                    .get(&info.id())
+                                                    .copied()
+                                                    .unwrap_or(false);
+                                                if raw_state == rules::CameraState::Inactive
+                                                    && previous_raw_state
+                                                        == Some(rules::CameraState::Unknown)
+                                                    && !ever_active
+                                                {
+                                                    probe_without_stream_count.set(
+                                                        probe_without_stream_count.get() + 1,
+                                                    );
+                                                    log::debug!(
+                                                        "id:{} probed the camera (Creating -> \
+                                                         Idle/Suspended) without ever reaching \
+                                                         Running",
+                                                        info.id()
+                                                    );
+                                                }
+                                            }
+                                            let app = info
+                                                .props()
+                                                .and_then(|props| props.get("application.name"));
+                                            // `--app-allow`: a camera held by an app that isn't
+                                            // on the (non-empty) allowlist is treated as idle
+                                            // rather than active, same as `Inactive` elsewhere —
+                                            // reuses the existing brightness mapping instead of a
+                                            // separate "ignored" concept.
+                                            let camera_state = if raw_state.is_active()
+                                                && !rules::is_allowed_app(app, &app_allowlist)
+                                            {
+                                                log::debug!(
+                                                    "id:{} camera running but app {:?} isn't on \
+                                                     the --app-allow list, treating as idle",
+                                                    info.id(),
+                                                    app
+                                                );
+                                                rules::CameraState::Inactive
+                                            } else {
+                                                raw_state
+                                            };
+                                            // `--require-format`: same "treat as idle rather
+                                            // than active" demotion as `--app-allow` above, for
+                                            // a node that's `Running` but hasn't (yet, or ever)
+                                            // negotiated an actual capture format - a trivial
+                                            // probe rather than a real capture.
+                                            let camera_state = if camera_state.is_active()
+                                                && require_format
+                                                && !node_has_format
+                                                    .borrow()
+                                                    .get(&info.id())
+                                                    .copied()
+                                                    .unwrap_or(false)
+                                            {
+                                                rules::CameraState::Inactive
+                                            } else {
+                                                camera_state
+                                            };
+                                            let is_running = camera_state.is_active();
+                                            let previous_state = camera_states
+                                                .borrow_mut()
+                                                .insert(info.id(), camera_state);
+                                            // `--ir-lighting-policy`: classify once per `info`
+                                            // event, same as `camera_state` above, rather than
+                                            // only at first sight - cheap, and keeps this in
+                                            // sync if `api.libcamera.pixel-format` ever changed
+                                            // out from under an already-tracked node.
+                                            camera_is_ir.borrow_mut().insert(
+                                                info.id(),
+                                                info.props().map(rules::is_ir_camera).unwrap_or(false),
+                                            );
+                                            if is_running && previous_state != Some(camera_state) {
+                                                activation_count.set(activation_count.get() + 1);
+                                            }
+                                            if previous_state != Some(camera_state) {
+                                                let product = info
+                                                    .props()
+                                                    .and_then(|props| props.get("device.product.name"))
+                                                    .unwrap_or("")
+                                                    .to_string();
+                                                csv_event = Some((
+                                                    info.id(),
+                                                    product,
+                                                    app.unwrap_or("").to_string(),
+                                                    format!("{:?}", camera_state),
+                                                ));
+                                                let total = transition_count.get() + 1;
+                                                transition_count.set(total);
+                                                println!(
+                                                    "--count: transition #{}: id:{} {:?} -> {:?}",
+                                                    total,
+                                                    info.id(),
+                                                    previous_state,
+                                                    camera_state
                                                 );
+                                                if count_limit.is_some_and(|limit| total >= limit) {
+                                                    log::info!(
+                                                        "--count: reached {} state transitions, quitting",
+                                                        total
+                                                    );
+                                                    if let Some(main_loop) = main_loop_weak.upgrade() {
+                                                        main_loop.quit();
+                                                    }
+                                                }
+                                            }
+                                            if is_running {
+                                                if let Some(app) = app {
+                                                    camera_apps
+                                                        .borrow_mut()
+                                                        .insert(info.id(), app.to_string());
+                                                }
+                                            } else {
+                                                camera_apps.borrow_mut().remove(&info.id());
+                                            }
+                                            // `--notify-sandboxed`: same "track per-node,
+                                            // aggregate with any-one-is-enough" shape as
+                                            // `screencast_nodes`/`screencast_active` above,
+                                            // just without a dedicated LED to drive.
+                                            if is_running && info.props().map(rules::is_sandboxed).unwrap_or(false) {
+                                                sandboxed_nodes.borrow_mut().insert(info.id(), true);
+                                            } else {
+                                                sandboxed_nodes.borrow_mut().remove(&info.id());
+                                            }
+                                            let any_sandboxed = !sandboxed_nodes.borrow().is_empty();
+                                            if any_sandboxed != sandboxed_active.get() {
+                                                sandboxed_active.set(any_sandboxed);
+                                                if notify_sandboxed {
+                                                    let body = if any_sandboxed {
+                                                        "Sandboxed (portal-mediated) camera access started"
+                                                    } else {
+                                                        "Sandboxed (portal-mediated) camera access ended"
+                                                    };
+                                                    if let Err(err) = notification(
+                                                        i18n::messages().sandboxed_access_summary,
+                                                        body,
+                                                    ) {
+                                                        log::error!(
+                                                            "failed to send sandboxed-access notification: {:?}",
+                                                            err
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        if untracked_now {
+                                            camera_apps.borrow_mut().remove(&info.id());
+                                            sandboxed_nodes.borrow_mut().remove(&info.id());
+                                        }
+                                        // Arbitrary which app "wins" if multiple tracked
+                                        // cameras are running different apps at once; picking
+                                        // any one is still better than ignoring `--app-pattern`
+                                        // entirely in that (rare) case.
+                                        let active_duty = camera_apps
+                                            .borrow()
+                                            .values()
+                                            .find_map(|app| app_patterns.get(app).copied())
+                                            .unwrap_or(default_duty);
+                                        current_duty.set(active_duty);
+                                        // When several tracked nodes are in different
+                                        // states at once, the brightest of their mapped
+                                        // values wins (see `Config::brightness_map`),
+                                        // same spirit as the old "any running -> on".
+                                        let desired = desired_brightness(
+                                            &camera_states.borrow(),
+                                            &camera_is_ir.borrow(),
+                                            ir_lighting_policy,
+                                            &cfg,
+                                        );
+                                        // `--cluster-listen`/`--only-when-unlocked`/
+                                        // `--startup-quiet`/`--latch`/`--force-state-file`/
+                                        // `--camera-notify`'s kill switch, plus noting the
+                                        // result to `--long-session-warn` - see
+                                        // `clamp_led_brightness`.
+                                        let led_brightness = clamp_led_brightness(
+                                            desired,
+                                            cluster.as_deref(),
+                                            session_locked.as_deref(),
+                                            started_at,
+                                            args.startup_quiet,
+                                            latch.as_deref(),
+                                            force.as_deref(),
+                                            led_disabled.get(),
+                                            long_session_warn.as_deref(),
+                                        );
+                                        if let (Some(event_csv), Some((id, product, app, state))) =
+                                            (&event_csv, &csv_event)
+                                        {
+                                            event_csv.borrow_mut().log(
+                                                *id,
+                                                product,
+                                                app,
+                                                state,
+                                                led_brightness,
+                                            );
+                                        }
+                                        if let Some((_id, _product, app, state)) = &csv_event {
+                                            history.borrow_mut().push(app.clone(), state.clone());
+                                        }
+                                        log::info!("set led brightness: {}", led_brightness);
+                                        for aux_led in aux_leds.iter() {
+                                            aux_led.mirror(led_brightness == X13S_LED_BRIGHTNESS_ON);
+                                        }
+                                        if let Some(watch) = &watch {
+                                            let states: Vec<(u32, bool)> = camera_states
+                                                .borrow()
+                                                .iter()
+                                                .map(|(id, state)| (*id, state.is_active()))
+                                                .collect();
+                                            watch.borrow_mut().render(&states, led_brightness);
+                                        }
+                                        // While mid-suspend/resume (per `--smooth-suspend`),
+                                        // skip the write entirely rather than flicking the
+                                        // LED off then on as PipeWire re-enumerates; the next
+                                        // `info` event after resume settles will catch up.
+                                        let suspend_transition = suspended
+                                            .as_ref()
+                                            .is_some_and(|flag| flag.load(std::sync::atomic::Ordering::SeqCst));
+                                        // When software PWM is active, the PWM timer owns
+                                        // writing the LED based on `camera_states` directly.
+                                        // Writes are queued to the writer thread rather than
+                                        // performed here, so failures surface asynchronously
+                                        // (as a notification sent from `writer.rs`) rather
+                                        // than being observable at this call site.
+                                        let write_issued = if !(args.pulse
+                                            || args.als_scale.is_some()
+                                            || software_pwm
+                                            || suspend_transition)
+                                        {
+                                            if let Some(limiter) = &rate_limiter {
+                                                limiter.request(led_brightness)
+                                            } else {
+                                                led_writer.request(led_brightness);
+                                                true
+                                            }
+                                        } else {
+                                            false
+                                        };
+                                        if trace_state_machine {
+                                            log_state_machine_trace(
+                                                "info",
+                                                &camera_states.borrow(),
+                                                desired,
+                                                led_brightness,
+                                                write_issued,
+                                            );
+                                        }
+                                        let now_on = led_brightness == X13S_LED_BRIGHTNESS_ON;
+                                        if now_on != *led_turned_on_by_us.borrow() {
+                                            let cue = if now_on { &sound_on } else { &sound_off };
+                                            if let Some(path) = cue.as_ref() {
+                                                sound::play_async(path);
+                                            }
+                                            if camera_notify && now_on {
+                                                if let Err(err) = notify_action::send(
+                                                    i18n::messages().camera_state_changed_summary,
+                                                    &cfg.camera_label(),
+                                                    "camera-web-symbolic",
+                                                    notify_action::DISABLE_LED_ACTION,
+                                                    i18n::messages().disable_led_action_label,
+                                                ) {
+                                                    log::error!(
+                                                        "--camera-notify: failed to send notification: {:?}",
+                                                        err
+                                                    );
+                                                }
                                             }
                                         }
+                                        *led_turned_on_by_us.borrow_mut() = now_on;
+                                        last_led_brightness.set(led_brightness);
                                     } else {
                                         // TODO: can I stop listening this camera unrelated one?
                                     }
@@ -167,6 +3303,23 @@ fn monitor() -> anyhow::Result<()> {
 
                             nodes.borrow_mut().add_node_t(node, node_listener);
                             nodes.borrow_mut().add_proxy_listener(proxy_id, listener);
+
+                            let tracked_count = nodes.borrow().len();
+                            if tracked_count > max_nodes {
+                                log::warn!(
+                                    "tracked node count {} exceeds --max-nodes {} \
+                                     (possible listener leak or pathological churn)",
+                                    tracked_count,
+                                    max_nodes
+                                );
+                                if prune_excess_nodes {
+                                    if let Some(pruned) =
+                                        nodes.borrow_mut().prune_oldest(*camera_id.borrow())
+                                    {
+                                        log::warn!("pruned oldest non-camera node id:{}", pruned);
+                                    }
+                                }
+                            }
                         }
                         _ => (),
                     }
@@ -174,63 +3327,767 @@ fn monitor() -> anyhow::Result<()> {
             }
         })
         .global_remove(move |id| {
+            if trace_registry {
+                log::info!("global_remove id:{}", id);
+            }
+            camera_states.borrow_mut().remove(&id);
+            camera_is_ir.borrow_mut().remove(&id);
+            rediscover_cache.borrow_mut().remove(&id);
+            camera_apps.borrow_mut().remove(&id);
             if *camera_id.borrow() == Some(id) {
                 log::info!("id:{} my camera removed", id);
                 *camera_id.borrow_mut() = None;
+                // The node just vanished rather than transitioning to
+                // `Inactive`/`Error` first, so there's no later `info`
+                // event to notice it's gone and turn the LED off -
+                // recompute the aggregate over whatever's left and write
+                // it now, through the exact same `clamp_led_brightness`
+                // clamp chain the `info` handler applies, so a
+                // removal-caused write can't bypass a forced override,
+                // an unlocked-session clamp, or `--startup-quiet`.
+                let desired = desired_brightness(
+                    &camera_states.borrow(),
+                    &camera_is_ir.borrow(),
+                    ir_lighting_policy,
+                    &cfg_for_remove,
+                );
+                let led_brightness = clamp_led_brightness(
+                    desired,
+                    cluster_for_remove.as_deref(),
+                    session_locked_for_remove.as_deref(),
+                    started_at,
+                    args.startup_quiet,
+                    latch_for_remove.as_deref(),
+                    force_for_remove.as_deref(),
+                    led_disabled_for_remove.get(),
+                    long_session_warn_for_remove.as_deref(),
+                );
+                log::info!("id:{} removed, recomputed led brightness: {}", id, led_brightness);
+                led_writer_for_remove.request(led_brightness);
+                if trace_state_machine {
+                    log_state_machine_trace(
+                        "global_remove",
+                        &camera_states.borrow(),
+                        desired,
+                        led_brightness,
+                        true,
+                    );
+                }
+                let now_on = led_brightness == X13S_LED_BRIGHTNESS_ON;
+                if now_on != *led_turned_on_by_us_for_remove.borrow() {
+                    let cue = if now_on { &sound_on_for_remove } else { &sound_off_for_remove };
+                    if let Some(path) = cue.as_ref() {
+                        sound::play_async(path);
+                    }
+                }
+                *led_turned_on_by_us_for_remove.borrow_mut() = now_on;
+                last_led_brightness_for_remove.set(led_brightness);
+                for aux_led in aux_leds_for_remove.iter() {
+                    aux_led.mirror(now_on);
+                }
+                if let Some(watch) = &watch_for_remove {
+                    let states: Vec<(u32, bool)> = camera_states
+                        .borrow()
+                        .iter()
+                        .map(|(id, state)| (*id, state.is_active()))
+                        .collect();
+                    watch.borrow_mut().render(&states, led_brightness);
+                }
+            }
+            if let Some(screencast_writer) = &screencast_writer {
+                screencast_nodes.borrow_mut().remove(&id);
+                let any_screencast = !screencast_nodes.borrow().is_empty();
+                let any_screencast = match &screencast_debounce {
+                    Some(debounce) => debounce.apply(any_screencast),
+                    None => any_screencast,
+                };
+                if any_screencast != screencast_active.get() {
+                    screencast_active.set(any_screencast);
+                    screencast_writer.request(if any_screencast {
+                        X13S_LED_BRIGHTNESS_ON
+                    } else {
+                        X13S_LED_BRIGHTNESS_OFF
+                    });
+                }
             }
+            // `--audio-led`: same "just drop the removed id and recompute"
+            // shape as `screencast_nodes` right above - an audio sink
+            // vanishing without a prior running-state transition (e.g. the
+            // device being unplugged) is rare enough not to warrant its
+            // own notification here either.
+            if let Some(audio_writer) = &audio_writer {
+                audio_nodes.borrow_mut().remove(&id);
+                let any_audio = !audio_nodes.borrow().is_empty();
+                let any_audio = match &audio_debounce {
+                    Some(debounce) => debounce.apply(any_audio),
+                    None => any_audio,
+                };
+                if any_audio != audio_active.get() {
+                    audio_active.set(any_audio);
+                    audio_writer.request(if any_audio {
+                        X13S_LED_BRIGHTNESS_ON
+                    } else {
+                        X13S_LED_BRIGHTNESS_OFF
+                    });
+                }
+            }
+            // `--notify-sandboxed`: same "just drop the removed id and
+            // recompute" shape as `screencast_nodes` above - a sandboxed
+            // node vanishing without a prior `info` transition is rare
+            // enough (and inherently untracked-camera-adjacent) not to
+            // warrant its own notification here.
+            sandboxed_nodes.borrow_mut().remove(&id);
+            sandboxed_active.set(!sandboxed_nodes.borrow().is_empty());
         })
         .register();
 
+    // `--rediscover-interval`: re-bind every node `rediscover_cache` has
+    // seen and re-run just the identification decision against its
+    // freshly-delivered `info`, logging when that disagrees with what
+    // `camera_id` currently says. Deliberately read-only: applying a
+    // mismatch (updating `camera_id`/`camera_states`, writing the LED,
+    // firing `--camera-notify`) is left to the per-node handler above,
+    // whose own next real `info` event will pick it up the normal way -
+    // running that whole side-effecting pipeline a second time, from a
+    // second independent listener on the same node, risks exactly the
+    // kind of double-write/race this daemon otherwise goes out of its way
+    // to avoid (see `rate_limiter`, `min_write_interval`).
+    if let Some(interval) = rediscover_interval {
+        let registry_weak = registry_weak.clone();
+        let rediscover_cache = rediscover_cache.clone();
+        let camera_id = camera_id.clone();
+        let ignore_nodes = ignore_nodes.clone();
+        let exclude_roles = exclude_roles.clone();
+        let pin_object_path = pin_object_path.clone();
+        let cfg = cfg.clone();
+        let in_scope_uids = in_scope_uids.clone();
+        let timer = main_loop.loop_().add_timer(move |_expirations| {
+            let Some(registry) = registry_weak.upgrade() else {
+                return;
+            };
+            for (id, obj) in rediscover_cache.borrow().iter() {
+                let node: pipewire::node::Node = match registry.bind(obj) {
+                    Ok(node) => node,
+                    Err(err) => {
+                        log::debug!(
+                            "--rediscover-interval: id:{} failed to re-bind, skipping: {:?}",
+                            id,
+                            err
+                        );
+                        continue;
+                    }
+                };
+                let id = *id;
+                let camera_id = camera_id.clone();
+                let ignore_nodes = ignore_nodes.clone();
+                let exclude_roles = exclude_roles.clone();
+                let pin_object_path = pin_object_path.clone();
+                let cfg = cfg.clone();
+                let in_scope_uids = in_scope_uids.clone();
+                let listener = node
+                    .add_listener_local()
+                    .info(move |info| {
+                        let ignored = info
+                            .props()
+                            .map(|props| {
+                                rules::is_ignored(info.id(), props, &ignore_nodes)
+                                    || rules::is_excluded_role(props, &exclude_roles)
+                                    || (only_my_nodes
+                                        && own_uid
+                                            .map(|uid| rules::is_other_users_node(props, uid))
+                                            .unwrap_or(false))
+                                    || in_scope_uids.as_ref().is_some_and(|in_scope| {
+                                        rules::is_out_of_session_scope(props, &in_scope.lock().unwrap())
+                                    })
+                            })
+                            .unwrap_or(false);
+                        let would_track = if ignored {
+                            false
+                        } else if let Some(path) = pin_object_path.as_ref() {
+                            info.props()
+                                .map(|props| rules::matches_object_path(props, path))
+                                .unwrap_or(false)
+                        } else if any_camera {
+                            info.props().map(rules::matches_any_camera).unwrap_or(false)
+                        } else {
+                            info.props()
+                                .map(|props| {
+                                    if cfg.match_threshold.is_some() {
+                                        rules::matches_camera_scored(props, &cfg)
+                                    } else {
+                                        rules::matches_camera(props, &cfg)
+                                    }
+                                })
+                                .unwrap_or(false)
+                        };
+                        let currently_tracked = *camera_id.borrow() == Some(id);
+                        if would_track != currently_tracked {
+                            log::warn!(
+                                "--rediscover-interval: id:{} identification changed on re-check \
+                                 (was tracked={}, now tracked={}); will take effect on this \
+                                 node's next real state change",
+                                id,
+                                currently_tracked,
+                                would_track
+                            );
+                        }
+                    })
+                    .register();
+                // One-shot: the listener exists only to receive the single
+                // `info` event PipeWire sends as soon as it's attached,
+                // same as every other bind site in this file. Leaked
+                // immediately, not stored, since there's nothing further
+                // for it to do afterward.
+                std::mem::forget(listener);
+                std::mem::forget(node);
+            }
+        });
+        let _ = timer.update_timer(Some(interval), Some(interval));
+        std::mem::forget(timer);
+    }
+
     main_loop.run();
 
+    if args.off_on_exit == OffOnExit::IfWeTurnedItOn && *led_turned_on_by_us.borrow() {
+        log::info!("turning LED off on exit, since we were the one who turned it on");
+        // Block until this is actually written (unlike `request`), since
+        // nothing downstream will wait for the writer thread otherwise.
+        led_writer.request_blocking(X13S_LED_BRIGHTNESS_OFF);
+        last_led_brightness.set(X13S_LED_BRIGHTNESS_OFF);
+    }
+
+    // `--shutdown-indicator`: applied after `--off-on-exit` above, so it
+    // can override that flag's "only if we turned it on" guard with an
+    // unconditional final state for the gap before a replacement process
+    // (e.g. a `systemctl restart`) attaches. Blocking writes throughout,
+    // same reasoning as `--off-on-exit`'s write above — nothing
+    // downstream waits for the writer thread otherwise.
+    match args.shutdown_indicator {
+        ShutdownIndicator::None => {}
+        ShutdownIndicator::Off => {
+            log::info!("--shutdown-indicator=off: forcing LED off on exit");
+            led_writer.request_blocking(X13S_LED_BRIGHTNESS_OFF);
+            last_led_brightness.set(X13S_LED_BRIGHTNESS_OFF);
+        }
+        ShutdownIndicator::Blink => {
+            log::info!("--shutdown-indicator=blink: blinking LED to signal the monitor is restarting");
+            for _ in 0..3 {
+                led_writer.request_blocking(X13S_LED_BRIGHTNESS_ON);
+                std::thread::sleep(Duration::from_millis(150));
+                led_writer.request_blocking(X13S_LED_BRIGHTNESS_OFF);
+                std::thread::sleep(Duration::from_millis(150));
+            }
+            last_led_brightness.set(X13S_LED_BRIGHTNESS_OFF);
+        }
+    }
+
+    // Leak check: by shutdown, `nodes` should hold at most the tracked
+    // camera (global_remove cleans up every other node as it disappears,
+    // and PipeWire tears down every remaining proxy when `core` drops
+    // right after this). Anything beyond that is a listener we should
+    // have stopped tracking somewhere along the way but didn't - the same
+    // "stop listening to unrelated nodes" regression the periodic
+    // `tracked nodes:` debug log above is watching for, just checked once
+    // here where there's a definite right answer (0 or 1) instead of a
+    // trend to eyeball.
+    {
+        let nodes = nodes.borrow();
+        let expected = if camera_id.borrow().is_some() { 1 } else { 0 };
+        if nodes.len() > expected || nodes.listener_count() > expected {
+            log::warn!(
+                "leaked node listeners at shutdown: nodes_t={} listeners={} (expected at most {})",
+                nodes.len(),
+                nodes.listener_count(),
+                expected
+            );
+        }
+    }
+
+    // One consolidated diagnostic line, for bug reports, summarizing how
+    // this run ended: `shutdown_reason` is "unknown" only if `monitor()`
+    // somehow returns without the main loop ever having quit via a signal
+    // or a core error, which shouldn't happen in practice.
+    log::info!(
+        "shutdown report: reason={} uptime={:?} camera_activations={} led_errors={} \
+         final_led_brightness={} probes_without_stream={}",
+        shutdown_reason.get(),
+        started_at.elapsed(),
+        activation_count.get(),
+        writer::error_count(),
+        last_led_brightness.get(),
+        probe_without_stream_count.get(),
+    );
+
     Rc::into_inner(result)
         .context("leak `result` reference somewhere")?
         .into_inner()
 }
 
-fn set_led_brightness(brightness: u32) -> anyhow::Result<()> {
-    static CONNECTION: OnceLock<zbus::Result<Connection>> = OnceLock::new();
-    let connection = CONNECTION
-        .get_or_init(Connection::system)
-        .clone()
-        .context("error connecting to system bus")?;
-    let _m = connection.call_method(
-        Some("org.freedesktop.login1"),
-        "/org/freedesktop/login1/session/auto",
-        Some("org.freedesktop.login1.Session"),
-        "SetBrightness",
-        &("leds", X13S_LED_DEVICE_NAME, brightness),
-    )?;
-    Ok(())
+pub(crate) fn notification(summary: &str, message: &str) -> anyhow::Result<()> {
+    let connection = Connection::session()?;
+    let summary = summary.to_string();
+    let message = message.to_string();
+    led::call_with_timeout(move || {
+        let _m = connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "org.u7fa9.x13s-camera-led",
+                ERROR_NOTIFICATION_ID,
+                "camera-web-symbolic",
+                summary,
+                message,
+                vec![""; 0],
+                HashMap::<&str, &Value>::new(),
+                0,
+            ),
+        )?;
+        Ok(())
+    })
 }
 
-fn notification(summary: &str, message: &str) -> anyhow::Result<()> {
+/// Close a previously-sent notification by id, e.g. to dismiss a
+/// `--persist-error-status` notification once the write it was about
+/// succeeds. Not an error if the notification is already gone.
+pub(crate) fn close_notification(id: u32) -> anyhow::Result<()> {
     let connection = Connection::session()?;
-    let _m = connection.call_method(
-        Some("org.freedesktop.Notifications"),
-        "/org/freedesktop/Notifications",
-        Some("org.freedesktop.Notifications"),
-        "Notify",
-        &(
-            "org.u7fa9.x13s-camera-led",
-            42u32,
-            "camera-web-symbolic",
-            summary,
-            message,
-            vec![""; 0],
-            HashMap::<&str, &Value>::new(),
-            0,
-        ),
-    )?;
-    Ok(())
+    led::call_with_timeout(move || {
+        let _m = connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "CloseNotification",
+            &(id,),
+        )?;
+        Ok(())
+    })
 }
 
 fn main() -> anyhow::Result<()> {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = parse_args();
+
+    if let Some(shell) = &args.completions {
+        // Needs neither logging nor PipeWire, so runs before either is set
+        // up, same as `--print-config` would if it didn't also echo
+        // `Config` fields derived from PipeWire-independent logic anyway.
+        print!("{}", completions::generate(shell).expect("validated in parse_args"));
+        return Ok(());
+    }
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .write_style(args.color_log)
+        .init();
+
+    led::set_dbus_timeout(args.dbus_timeout);
 
     pipewire::init();
 
-    monitor()?;
+    if args.check_session {
+        check::run(&led::default_backend())?;
+    } else if args.print_config {
+        print_config(&args);
+    } else if let Some(id) = args.dump_node {
+        dump_node(&args, id)?;
+    } else if args.status {
+        status(&args, args.status_json)?;
+    } else if let Some((on, off)) = args.simulate {
+        simulate(&args, on, off)?;
+    } else if let Some(path) = &args.replay {
+        let (camera_product_name, front_location, led_device_name, any_camera, pipeline_handler) =
+            resolve_identification(&args);
+        let cfg = config::Config {
+            camera_product_name,
+            front_location,
+            led_device_name,
+            exclude_ir: args.exclude_ir,
+            brightness_map: build_brightness_map(&args.state_brightness, args.early_on, args.standby_brightness),
+            match_weights: build_match_weights(&args.match_weights),
+            match_threshold: args.match_threshold,
+            pipeline_handler,
+            device_api: args.device_api.clone(),
+            device_serial: args.device_serial.clone(),
+            ..config::Config::default()
+        };
+        replay::run(path, &cfg, any_camera)?;
+    } else if let Some(path) = &args.replay_states {
+        let (camera_product_name, front_location, led_device_name, any_camera, pipeline_handler) =
+            resolve_identification(&args);
+        let cfg = config::Config {
+            camera_product_name,
+            front_location,
+            led_device_name,
+            exclude_ir: args.exclude_ir,
+            brightness_map: build_brightness_map(&args.state_brightness, args.early_on, args.standby_brightness),
+            match_weights: build_match_weights(&args.match_weights),
+            match_threshold: args.match_threshold,
+            pipeline_handler,
+            device_api: args.device_api.clone(),
+            device_serial: args.device_serial.clone(),
+            ..config::Config::default()
+        };
+        replay::run_states(path, &cfg, any_camera)?;
+    } else {
+        monitor(&args)?;
+    }
+
+    Ok(())
+}
+
+/// Ignore PipeWire entirely and toggle the LED on a fixed on/off
+/// schedule, for demos and showroom units where there's no real camera
+/// to react to. Runs forever; stop with Ctrl-C (there's no main loop to
+/// clean up, so `--off-on-exit` doesn't apply here).
+fn simulate(args: &Args, on: Duration, off: Duration) -> anyhow::Result<()> {
+    let backend: Box<dyn LedBackend> = if let Some(command_template) = &args.led_command {
+        Box::new(led::CommandBackend::new(command_template.clone(), args.led_command_max))
+    } else if let Some(chip) = &args.gpio_chip {
+        #[cfg(feature = "gpio")]
+        {
+            Box::new(led::GpioBackend::new(chip, args.gpio_line.unwrap_or(0))?)
+        }
+        #[cfg(not(feature = "gpio"))]
+        {
+            anyhow::bail!(
+                "--gpio-chip {} was given but this binary wasn't built with the gpio feature",
+                chip
+            );
+        }
+    } else if args.brightness_percentage {
+        Box::new(led::default_backend_with_percentage())
+    } else {
+        Box::new(led::default_backend())
+    };
+    let led_writer = writer::LedWriter::spawn(
+        backend,
+        args.persist_error_status,
+        args.verify_write,
+        None,
+        args.notify_fallback,
+    );
+    loop {
+        log::info!("simulate: on for {:?}", on);
+        led_writer.request(X13S_LED_BRIGHTNESS_ON);
+        std::thread::sleep(on);
+        log::info!("simulate: off for {:?}", off);
+        led_writer.request(X13S_LED_BRIGHTNESS_OFF);
+        std::thread::sleep(off);
+    }
+}
+
+/// Print the effective identification rule and `--ignore-node` list,
+/// without connecting to PipeWire. A quick way to confirm what flags
+/// actually resolved to before chasing a match failure live.
+fn print_config(args: &Args) {
+    let (camera_product_name, front_location, led_device_name, any_camera, pipeline_handler) =
+        resolve_identification(args);
+    let cfg = config::Config {
+        camera_product_name,
+        front_location,
+        led_device_name,
+        exclude_ir: args.exclude_ir,
+        brightness_map: build_brightness_map(&args.state_brightness, args.early_on, args.standby_brightness),
+        match_weights: build_match_weights(&args.match_weights),
+        match_threshold: args.match_threshold,
+        pipeline_handler,
+        device_api: args.device_api.clone(),
+        device_serial: args.device_serial.clone(),
+        ..config::Config::default()
+    };
+    println!("profile: {:?}", args.profile);
+    println!("any_camera: {}", any_camera);
+    println!("camera_product_name: {}", cfg.camera_product_name);
+    println!("front_location: {}", cfg.front_location);
+    println!(
+        "front_location_synonyms: {:?}",
+        cfg.front_location_synonyms
+    );
+    println!("camera_label: {:?}", cfg.camera_label());
+    println!("led_device_name: {}", cfg.led_device_name);
+    println!("exclude_ir: {}", cfg.exclude_ir);
+    println!("ignore_nodes: {:?}", args.ignore_nodes);
+    println!("pin_object_path: {:?}", args.pin_object_path);
+    println!("expect_camera_within: {:?}", args.expect_camera_within);
+    println!("strict: {}", args.strict);
+    println!("aux_leds: {:?}", args.aux_leds);
+    println!("explain: {}", args.explain);
+    println!("persist_error_status: {}", args.persist_error_status);
+    println!("notify_fallback: {:?}", args.notify_fallback);
+    println!("app_patterns: {:?}", args.app_patterns);
+    println!("simulate: {:?}", args.simulate);
+    println!("exclude_roles: {:?}", args.exclude_roles);
+    println!("use_kernel_trigger: {}", args.use_kernel_trigger);
+    println!("brightness_map: {:?}", cfg.brightness_map);
+    let led_device_candidates: Vec<String> = if args.led_devices.is_empty() {
+        vec![cfg.led_device_name.clone()]
+    } else {
+        args.led_devices.clone()
+    };
+    println!("led_devices: {:?}", led_device_candidates);
+    println!(
+        "led_device (resolved): {:?}",
+        led::select_device(&led_device_candidates)
+    );
+    println!("max_nodes: {}", args.max_nodes);
+    println!("prune_excess_nodes: {}", args.prune_excess_nodes);
+    println!("early_on: {}", args.early_on);
+    println!("color_log: {:?}", args.color_log);
+    println!("replay: {:?}", args.replay);
+    println!("replay_states: {:?}", args.replay_states);
+    println!("standby_brightness: {:?}", args.standby_brightness);
+    println!("only_my_nodes: {}", args.only_my_nodes);
+    println!("session_scope: {:?}", args.session_scope);
+    println!("app_allowlist: {:?}", args.app_allowlist);
+    println!("sound_on: {:?}", args.sound_on);
+    println!("sound_off: {:?}", args.sound_off);
+    println!("match_threshold: {:?}", cfg.match_threshold);
+    println!("pipeline_handler: {:?}", cfg.pipeline_handler);
+    println!("device_api: {:?}", cfg.device_api);
+    println!("device_serial: {:?}", cfg.device_serial);
+    println!("on_node_error: {:?}", args.on_node_error);
+    println!("ir_lighting_policy: {:?}", args.ir_lighting_policy);
+    println!("notify_sandboxed: {}", args.notify_sandboxed);
+    println!("rediscover_interval: {:?}", args.rediscover_interval);
+    println!("state_file: {:?}", args.state_file);
+    println!("startup_quiet: {:?}", args.startup_quiet);
+    println!("force_state_file: {:?}", args.force_state_file);
+    println!("event_csv: {:?}", args.event_csv);
+    println!("history_size: {}", args.history_size);
+    println!("shutdown_indicator: {:?}", args.shutdown_indicator);
+    println!(
+        "match_weights: media_role={} location={} product_name={} not_ir={} pipeline_handler={} device_api={} device_serial={} (total={})",
+        cfg.match_weights.media_role,
+        cfg.match_weights.location,
+        cfg.match_weights.product_name,
+        cfg.match_weights.not_ir,
+        cfg.match_weights.pipeline_handler,
+        cfg.match_weights.device_api,
+        cfg.match_weights.device_serial,
+        cfg.match_weights.total()
+    );
+    println!(
+        "debug_probe_without_stream: {}",
+        args.debug_probe_without_stream
+    );
+    println!("require_format: {}", args.require_format);
+    println!("verify_write: {}", args.verify_write);
+    println!("max_event_latency_warn: {:?}", args.max_event_latency_warn);
+    println!("screencast_led: {:?}", args.screencast_led);
+    println!("screencast_notify: {}", args.screencast_notify);
+    println!("screencast_debounce: {:?}", args.screencast_debounce);
+    println!("audio_led: {:?}", args.audio_led);
+    println!("audio_notify: {}", args.audio_notify);
+    println!("audio_debounce: {:?}", args.audio_debounce);
+    println!("camera_notify: {}", args.camera_notify);
+    println!("startup_delay: {:?}", args.startup_delay);
+    println!("pulse: {}", args.pulse);
+    println!("pulse_curve: {:?}", args.pulse_curve);
+    println!("pulse_period: {:?}", args.pulse_period);
+    println!("als_scale: {:?}", args.als_scale);
+    println!("led_command: {:?}", args.led_command);
+    println!("led_command_max: {}", args.led_command_max);
+    println!("fallback_led_device: {:?}", args.fallback_led_device);
+    println!("only_when_unlocked: {}", args.only_when_unlocked);
+    println!("latch_clear: {:?}", args.latch_clear);
+    println!("dbus_timeout: {:?}", args.dbus_timeout);
+    println!("gpio_chip: {:?}", args.gpio_chip);
+    println!("gpio_line: {:?}", args.gpio_line);
+    println!("cluster_listen: {:?}", args.cluster_listen);
+    println!("cluster_peers: {:?}", args.cluster_peers);
+    println!("status: {}", args.status);
+    println!("status_json: {}", args.status_json);
+}
+
+/// One-shot snapshot of every `media.role=Camera` node plus the LED
+/// device's current brightness, for scripts/tray apps that want current
+/// state without scraping log output or running a full `--watch`
+/// session. `--json` switches from the human checklist to
+/// `{cameras:[{id,product,location,state}], led:{device,brightness,max}, forced}`.
+/// Shows every camera-role node, not just whichever one this daemon has
+/// identified as *the* front camera (see `matches_camera`) — a status
+/// dump is more useful listing everything than narrowed to one.
+///
+/// Quits after a short fixed wait for the registry to finish
+/// enumerating, since there's no "initial enumeration complete" signal
+/// from the core yet (`dump_node`'s single-node version avoids this by
+/// already knowing which id to wait for instead of waiting out a clock).
+fn status(args: &Args, json: bool) -> anyhow::Result<()> {
+    let main_loop = pipewire::main_loop::MainLoop::new(None)?;
+    let context = pipewire::context::Context::new(&main_loop)?;
+    let connect_props = args.pipewire_remote.as_ref().map(|remote| {
+        pipewire::properties::properties! {
+            *pipewire::keys::REMOTE_NAME => remote.as_str()
+        }
+    });
+    let core = context.connect(connect_props)?;
+    let registry = Rc::new(core.get_registry()?);
+    let registry_weak = Rc::downgrade(&registry);
+
+    let nodes = Rc::new(RefCell::new(Nodes::new()));
+    let cameras: Rc<RefCell<Vec<serde_json::Value>>> = Rc::new(RefCell::new(Vec::new()));
+    let main_loop_weak = main_loop.downgrade();
 
+    let _registry_listener = registry
+        .add_listener_local()
+        .global({
+            let cameras = cameras.clone();
+            let nodes = nodes.clone();
+            move |obj| {
+                if obj.type_ != ObjectType::Node {
+                    return;
+                }
+                if let Some(registry) = registry_weak.upgrade() {
+                    let node: Node = match registry.bind(obj) {
+                        Ok(node) => node,
+                        Err(err) => {
+                            log::debug!("id:{} failed to bind node, skipping: {:?}", obj.id, err);
+                            return;
+                        }
+                    };
+                    let cameras = cameras.clone();
+                    let node_listener = node
+                        .add_listener_local()
+                        .info(move |info| {
+                            let Some(props) = info.props() else { return };
+                            if props.get("media.role") != Some("Camera") {
+                                return;
+                            }
+                            cameras.borrow_mut().push(serde_json::json!({
+                                "id": info.id(),
+                                "product": props.get("device.product.name").unwrap_or(""),
+                                "location": props.get("api.libcamera.location").unwrap_or(""),
+                                "state": format!("{:?}", info.state()),
+                            }));
+                        })
+                        .register();
+                    nodes.borrow_mut().add_node_t(node, node_listener);
+                }
+            }
+        })
+        .register();
+
+    let main_loop_weak = main_loop.downgrade();
+    let timer = main_loop.loop_().add_timer(move |_expirations| {
+        if let Some(main_loop) = main_loop_weak.upgrade() {
+            main_loop.quit();
+        }
+    });
+    const ENUMERATION_WAIT: Duration = Duration::from_millis(500);
+    let _ = timer.update_timer(Some(ENUMERATION_WAIT), None);
+
+    main_loop.run();
+
+    let (_camera_product_name, _front_location, led_device_name, _any_camera, _pipeline_handler) =
+        resolve_identification(args);
+    let led_device_candidates: Vec<String> = if args.led_devices.is_empty() {
+        vec![led_device_name]
+    } else {
+        args.led_devices.clone()
+    };
+    let led_device_name = led::select_device(&led_device_candidates)
+        .unwrap_or_else(|| led_device_candidates[0].clone());
+    let backend = led::LogindBackend::with_candidates(led_device_candidates, args.brightness_percentage);
+    let max = backend.max_brightness().ok();
+    let brightness = led::read_brightness(&led_device_name).ok();
+
+    // `--force-state-file` is just a polled file, so this one-shot command
+    // can report the same override a running `monitor()` would be acting
+    // on without needing to talk to it at all - poll it fresh here rather
+    // than relying on any state a separate process might hold.
+    let forced = args.force_state_file.as_ref().map(|path| {
+        let force = force::Force::new(path.clone());
+        force.poll();
+        force.active()
+    });
+
+    if json {
+        let output = serde_json::json!({
+            "cameras": *cameras.borrow(),
+            "led": {
+                "device": led_device_name,
+                "brightness": brightness,
+                "max": max,
+            },
+            "forced": forced,
+        });
+        println!("{}", output);
+    } else {
+        println!("cameras:");
+        for camera in cameras.borrow().iter() {
+            println!("  {}", camera);
+        }
+        println!(
+            "led: device={} brightness={:?} max={:?}",
+            led_device_name, brightness, max
+        );
+        println!("forced: {:?}", forced);
+    }
+
+    Ok(())
+}
+
+/// Bind a single node by id, print its props and state once `info`
+/// arrives, then quit. Reuses the same registry/node-listener plumbing
+/// as `monitor()`, just scoped to one id and exiting after first info.
+fn dump_node(args: &Args, target_id: u32) -> anyhow::Result<()> {
+    let main_loop = pipewire::main_loop::MainLoop::new(None)?;
+    let context = pipewire::context::Context::new(&main_loop)?;
+    let connect_props = args.pipewire_remote.as_ref().map(|remote| {
+        pipewire::properties::properties! {
+            *pipewire::keys::REMOTE_NAME => remote.as_str()
+        }
+    });
+    let core = context.connect(connect_props)?;
+    let registry = Rc::new(core.get_registry()?);
+    let registry_weak = Rc::downgrade(&registry);
+
+    let nodes = Rc::new(RefCell::new(Nodes::new()));
+    let main_loop_weak = main_loop.downgrade();
+
+    let _registry_listener = registry
+        .add_listener_local()
+        .global(move |obj| {
+            if obj.id != target_id || obj.type_ != ObjectType::Node {
+                return;
+            }
+            if let Some(registry) = registry_weak.upgrade() {
+                let node: Node = match registry.bind(obj) {
+                    Ok(node) => node,
+                    Err(err) => {
+                        log::debug!("id:{} failed to bind node, skipping: {:?}", obj.id, err);
+                        return;
+                    }
+                };
+                let main_loop_weak = main_loop_weak.clone();
+                let node_listener = node
+                    .add_listener_local()
+                    .info(move |info| {
+                        println!("id: {}", info.id());
+                        println!("state: {:?}", info.state());
+                        if let Some(props) = info.props() {
+                            println!("props:");
+                            for (key, value) in props.iter() {
+                                println!("  {} = {}", key, value);
+                            }
+                            // Surfaced explicitly (rather than left for the
+                            // reader to spot among the raw props above)
+                            // since `pipewire.access.portal.*` isn't as
+                            // immediately recognizable as e.g.
+                            // `api.libcamera.pixel-format` - see
+                            // `rules::is_sandboxed`/`--notify-sandboxed`.
+                            println!("sandboxed: {}", rules::is_sandboxed(props));
+                        }
+                        if let Some(main_loop) = main_loop_weak.upgrade() {
+                            main_loop.quit();
+                        }
+                    })
+                    .register();
+                nodes.borrow_mut().add_node_t(node, node_listener);
+            }
+        })
+        .register();
+
+    main_loop.run();
     Ok(())
 }
@@ -0,0 +1,123 @@
+//! Desktop-notification actions (buttons), for `--camera-notify`'s
+//! "disable the LED" button. `main::notification` is purely
+//! fire-and-forget — none of its call sites offer an action, so nothing
+//! there listens for the notification daemon's `ActionInvoked` signal.
+//! This module is the half that does both: [`send`] sends a `Notify`
+//! call with one action, [`watch`] listens for it being invoked.
+//!
+//! [`watch`] is the same "dedicated thread, zbus blocking signal
+//! iterator, `Arc<AtomicBool>` flag" shape as `sessionlock::watch`/
+//! `suspend::watch` — a signal iterator blocks for the life of the
+//! connection, which doesn't fit this daemon's non-blocking,
+//! callback-driven style around `pipewire::main_loop::MainLoop`. Only
+//! one action key is watched for per call, matched regardless of which
+//! notification id it came from: every current caller only ever has one
+//! notification with actions in flight at a time, so there's nothing to
+//! disambiguate by id yet.
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::Value;
+
+/// The action key `--camera-notify` offers on its notification, and the
+/// one [`watch`] is called with for it.
+pub const DISABLE_LED_ACTION: &str = "disable-led";
+
+/// How often `monitor()`'s `--camera-notify` timer checks [`watch`]'s
+/// flag. `ActionInvoked` itself is a signal with no deadline the user
+/// cares about meeting to the millisecond (it's a button click), so this
+/// is simply reused from `force::POLL_INTERVAL`'s same-shaped polling.
+pub const ACTION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Send a notification offering a single action button
+/// (`action_key`/`action_label`, per the `org.freedesktop.Notifications`
+/// `Notify` actions array: key/label pairs). Always requests a fresh id
+/// (`replaces_id: 0`) rather than reusing one like
+/// `main::notification`'s `ERROR_NOTIFICATION_ID` does — replacing an
+/// older notification here would make its still-visible action button
+/// silently stop doing anything.
+pub fn send(
+    summary: &str,
+    message: &str,
+    icon: &str,
+    action_key: &str,
+    action_label: &str,
+) -> anyhow::Result<()> {
+    let connection = Connection::session()?;
+    let summary = summary.to_string();
+    let message = message.to_string();
+    let icon = icon.to_string();
+    let action_key = action_key.to_string();
+    let action_label = action_label.to_string();
+    crate::led::call_with_timeout(move || {
+        let _m = connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "org.u7fa9.x13s-camera-led",
+                0u32,
+                icon,
+                summary,
+                message,
+                vec![action_key, action_label],
+                HashMap::<&str, &Value>::new(),
+                0,
+            ),
+        )?;
+        Ok(())
+    })
+}
+
+/// Watch for `ActionInvoked` naming `action_key`, on a dedicated thread,
+/// and flip the returned flag when it happens. Never clears the flag
+/// back to `false` itself — callers that want single-shot "did this just
+/// get clicked" behavior check-and-clear it themselves (see
+/// `monitor()`'s `--camera-notify` polling timer).
+pub fn watch(action_key: &'static str) -> anyhow::Result<Arc<AtomicBool>> {
+    let connection = Connection::session()?;
+    let invoked = Arc::new(AtomicBool::new(false));
+    let flag = invoked.clone();
+
+    thread::spawn(move || {
+        let proxy = match Proxy::new(
+            &connection,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        ) {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                log::error!("notify_action: failed to open Notifications proxy: {:?}", err);
+                return;
+            }
+        };
+        let signals = match proxy.receive_signal("ActionInvoked") {
+            Ok(signals) => signals,
+            Err(err) => {
+                log::error!("notify_action: failed to subscribe to ActionInvoked: {:?}", err);
+                return;
+            }
+        };
+        for signal in signals {
+            match signal.body().deserialize::<(u32, String)>() {
+                Ok((_id, key)) => {
+                    if key == action_key {
+                        flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                    }
+                }
+                Err(err) => {
+                    log::warn!("notify_action: malformed ActionInvoked signal: {:?}", err)
+                }
+            }
+        }
+    });
+
+    Ok(invoked)
+}
@@ -0,0 +1,96 @@
+//! `--notify-fallback`: a way to surface an important notification (today,
+//! only the LED-write-failure one `writer::write` sends) when the session
+//! bus `notification()` needs isn't available at all, e.g. a headless box
+//! or a system service with no logged-in desktop session. `notification()`
+//! itself still always tries the bus first; this only runs when that call
+//! fails, and never fires on its own.
+
+use std::os::unix::net::UnixDatagram;
+use std::process::{Command, Stdio};
+
+/// Where a failed desktop notification should go instead. `None` (no
+/// `--notify-fallback`) just logs the failure, the prior behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyFallback {
+    /// Write to the systemd journal with `PRIORITY=3` (err), via the
+    /// native journal socket protocol — see [`journal`]. A no-op (logged
+    /// as a warning) outside systemd, where `/run/systemd/journal/socket`
+    /// doesn't exist.
+    Journal,
+    /// Broadcast via `wall(1)`, present on essentially every Linux distro
+    /// without an extra dependency on this crate's side, same rationale
+    /// `sound.rs` gives for shelling out to `aplay` instead of linking a
+    /// library.
+    Wall,
+}
+
+pub fn parse(value: &str) -> Result<NotifyFallback, String> {
+    match value {
+        "journal" => Ok(NotifyFallback::Journal),
+        "wall" => Ok(NotifyFallback::Wall),
+        other => Err(format!(
+            "unknown value {:?}, expected journal/wall",
+            other
+        )),
+    }
+}
+
+/// Send `summary`/`message` through `fallback`, having already failed to
+/// deliver it as a real desktop notification. Errors here are only logged,
+/// never propagated — by the time this runs, the caller's own notification
+/// attempt has already failed, and there's nowhere further to fall back to.
+pub fn send(fallback: NotifyFallback, summary: &str, message: &str) {
+    match fallback {
+        NotifyFallback::Journal => journal(summary, message),
+        NotifyFallback::Wall => wall(summary, message),
+    }
+}
+
+/// Write directly to systemd's journal socket, bypassing `log`/
+/// `env_logger` (which this daemon already sends to stderr, itself
+/// normally journal-captured under a systemd unit) so this is visible
+/// even when stderr isn't journal-captured, and so it's marked `err`
+/// priority regardless of the daemon's own `RUST_LOG` level. Implemented
+/// by hand against journald's native datagram protocol (simple
+/// `KEY=value` lines) rather than linking `libsystemd`, same "it's just a
+/// socket and a few env vars" approach `systemd.rs` takes for
+/// `sd_notify`/socket activation.
+fn journal(summary: &str, message: &str) {
+    let socket = match UnixDatagram::unbound() {
+        Ok(socket) => socket,
+        Err(err) => {
+            log::warn!("--notify-fallback=journal: failed to create socket: {:?}", err);
+            return;
+        }
+    };
+    // Priority 3 is `LOG_ERR`; see `sd-journal(3)`'s PRIORITY field. Values
+    // are assumed not to contain a newline (true of every caller today,
+    // all short one-line error strings) - the native protocol's
+    // length-prefixed framing for multi-line values isn't implemented
+    // here since nothing needs it yet.
+    let payload = format!(
+        "PRIORITY=3\nSYSLOG_IDENTIFIER=x13s-camera-led\nMESSAGE={}: {}\n",
+        summary, message
+    );
+    if let Err(err) = socket.send_to(payload.as_bytes(), "/run/systemd/journal/socket") {
+        log::warn!("--notify-fallback=journal: failed to send: {:?}", err);
+    }
+}
+
+/// Broadcast to every logged-in terminal via `wall(1)`. Spawned
+/// fire-and-forget, not waited on, the same "don't let a slow/hanging
+/// child delay the caller" shape `sound::play_async` uses for `aplay`,
+/// except there's nothing here worth spawning a dedicated thread to wait
+/// on afterward - a notification fallback's own failure isn't itself
+/// actionable.
+fn wall(summary: &str, message: &str) {
+    if let Err(err) = Command::new("wall")
+        .arg(format!("{}: {}", summary, message))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        log::warn!("--notify-fallback=wall: failed to spawn wall: {:?}", err);
+    }
+}
@@ -0,0 +1,132 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use pipewire::loop_::LoopRef;
+
+use crate::rules::CameraState;
+use crate::writer::LedWriter;
+
+/// Tick rate for the pulse timer. Faster than `pwm::TICK` since a
+/// breathing effect on a dimmable LED reads as choppy at 50Hz the way a
+/// binary on/off blink doesn't.
+const TICK: Duration = Duration::from_millis(10);
+
+/// A brightness-over-time shape for `--pulse-curve`, sampled at a phase
+/// in `0.0..1.0` (one full period) and returning a level in `0.0..=1.0`
+/// to be scaled by the LED's `max_brightness`.
+#[derive(Debug, Clone)]
+pub enum Curve {
+    /// A smooth breathing curve: `(sin(phase * 2π) + 1) / 2`.
+    Sine,
+    /// A linear ramp up then down, peaking at the midpoint of the period.
+    Triangle,
+    /// Explicit levels (each `0.0..=1.0`), evenly spaced across the
+    /// period and linearly interpolated between, for a shape neither
+    /// built-in curve covers.
+    Keyframes(Vec<f64>),
+}
+
+impl Curve {
+    /// Sample this curve at `phase` (wrapped into `0.0..1.0`).
+    pub fn sample(&self, phase: f64) -> f64 {
+        let phase = phase.rem_euclid(1.0);
+        match self {
+            Curve::Sine => (f64::sin(phase * std::f64::consts::TAU) + 1.0) / 2.0,
+            Curve::Triangle => {
+                if phase < 0.5 {
+                    phase * 2.0
+                } else {
+                    2.0 - phase * 2.0
+                }
+            }
+            Curve::Keyframes(levels) => {
+                if levels.is_empty() {
+                    return 0.0;
+                }
+                if levels.len() == 1 {
+                    return levels[0];
+                }
+                let segments = (levels.len() - 1) as f64;
+                let position = phase * segments;
+                let index = (position.floor() as usize).min(levels.len() - 2);
+                let fraction = position - index as f64;
+                levels[index] + (levels[index + 1] - levels[index]) * fraction
+            }
+        }
+    }
+}
+
+/// Parse a `--pulse-curve` value: `sine`, `triangle`, or
+/// `keyframes:<level>,<level>,...` with each level in `0.0..=1.0`.
+pub fn parse_curve(spec: &str) -> Result<Curve, String> {
+    match spec {
+        "sine" => Ok(Curve::Sine),
+        "triangle" => Ok(Curve::Triangle),
+        other => {
+            let levels = other
+                .strip_prefix("keyframes:")
+                .ok_or_else(|| {
+                    format!(
+                        "--pulse-curve: unrecognized curve {:?}, expected sine/triangle/keyframes:<levels>",
+                        other
+                    )
+                })?
+                .split(',')
+                .map(|level| {
+                    level
+                        .parse::<f64>()
+                        .map_err(|_| format!("--pulse-curve: invalid keyframe level {:?}", level))
+                })
+                .collect::<Result<Vec<f64>, String>>()?;
+            if levels.len() < 2 {
+                return Err("--pulse-curve: keyframes needs at least 2 levels".to_string());
+            }
+            Ok(Curve::Keyframes(levels))
+        }
+    }
+}
+
+/// Render `curve` as a breathing effect on a dimmable LED while any
+/// tracked camera is active, scaled to `max_brightness`, stopping
+/// cleanly (one final off write) as soon as the camera turns off —
+/// same shape as `pwm::start`'s on/off tracking, just with a continuous
+/// curve instead of a fixed duty cycle.
+pub fn start(
+    loop_: &LoopRef,
+    writer: LedWriter,
+    camera_states: Rc<std::cell::RefCell<HashMap<u32, CameraState>>>,
+    curve: Curve,
+    period: Duration,
+    max_brightness: u32,
+) {
+    let elapsed = Rc::new(Cell::new(Duration::ZERO));
+    let was_off = Rc::new(Cell::new(true));
+    let period_secs = period.as_secs_f64().max(0.001);
+
+    let timer = loop_.add_timer(move |_expirations| {
+        let camera_running = camera_states.borrow().values().any(CameraState::is_active);
+
+        if !camera_running {
+            elapsed.set(Duration::ZERO);
+            if !was_off.get() {
+                was_off.set(true);
+                writer.request(0);
+            }
+            return;
+        }
+        was_off.set(false);
+
+        elapsed.set(elapsed.get() + TICK);
+        let phase = elapsed.get().as_secs_f64() / period_secs;
+        let level = curve.sample(phase).clamp(0.0, 1.0);
+        let brightness = (level * max_brightness as f64).round() as u32;
+        writer.request(brightness);
+    });
+
+    let _ = timer.update_timer(Some(TICK), Some(TICK));
+    // Intentionally leaked, same as `pwm::start`'s timer: it must outlive
+    // this function and ticks harmlessly for the life of the process.
+    std::mem::forget(timer);
+}
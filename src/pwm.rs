@@ -0,0 +1,70 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use pipewire::loop_::LoopRef;
+
+use crate::writer::LedWriter;
+
+/// Tick rate for software PWM. 20ms (50Hz) is fast enough that flicker
+/// isn't very noticeable but slow enough not to hammer the backend.
+const TICK: Duration = Duration::from_millis(20);
+
+/// Software PWM for LEDs that only support 0/1 brightness: ticks a
+/// repeating main-loop timer and turns the LED on for `duty` out of
+/// every 100 ticks, emulating dimming without hardware PWM. This is
+/// software PWM with the usual limitations — timing jitter from the main
+/// loop, and a visible flicker/cycle rate (here, 100 ticks = 2s) rather
+/// than a true steady dim level.
+///
+/// `duty` is a shared cell rather than a plain value so `--app-pattern`
+/// can retarget it live as the app holding the camera changes, without
+/// needing to restart this timer.
+///
+/// The timer itself can't be disarmed from the per-node callback that
+/// detects the camera stopping (pipewire-rs requires timer and listener
+/// callbacks to be `'static`, which rules out a callback holding a
+/// reference back to its own non-`'static` `TimerSource`). Instead, it
+/// just ticks for the life of the daemon and skips the D-Bus write
+/// whenever the camera isn't running, so idle cost is one cheap local
+/// check per tick rather than a SetBrightness call.
+pub fn start(
+    loop_: &LoopRef,
+    writer: LedWriter,
+    camera_states: Rc<std::cell::RefCell<HashMap<u32, bool>>>,
+    duty: Rc<Cell<u8>>,
+    on: u32,
+    off: u32,
+) {
+    let tick = Rc::new(Cell::new(0u32));
+    let was_off = Rc::new(Cell::new(true));
+
+    let timer = loop_.add_timer(move |_expirations| {
+        let camera_running = camera_states.borrow().values().any(|running| *running);
+
+        if !camera_running {
+            tick.set(0);
+            if !was_off.get() {
+                was_off.set(true);
+                writer.request(off);
+            }
+            return;
+        }
+        was_off.set(false);
+
+        // Read fresh every tick rather than captured once, so a per-app
+        // pattern switch (`--app-pattern`) takes effect on the very next
+        // tick rather than only for newly-started PWM runs.
+        let duty = (duty.get() as u32).min(100);
+        let phase = tick.get();
+        tick.set((phase + 1) % 100);
+        let brightness = if phase < duty { on } else { off };
+        writer.request(brightness);
+    });
+
+    let _ = timer.update_timer(Some(TICK), Some(TICK));
+    // Intentionally leaked: the timer must outlive this function and
+    // ticks harmlessly for the life of the process (see doc comment).
+    std::mem::forget(timer);
+}
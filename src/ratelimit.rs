@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use pipewire::loop_::LoopRef;
+
+use crate::writer::LedWriter;
+
+/// Rate-limits writes through a [`LedWriter`]: if a new value arrives
+/// sooner than `min_interval` after the last queued write, it's
+/// coalesced — only the latest pending value is kept, and it's flushed
+/// once the interval has elapsed. Complements decision-side debounce;
+/// this is a limiter on the write side itself, to avoid hammering logind
+/// under pathological flapping.
+pub struct RateLimitedWriter {
+    writer: LedWriter,
+    min_interval: Duration,
+    last_write: RefCell<Option<Instant>>,
+    pending: RefCell<Option<u32>>,
+}
+
+impl RateLimitedWriter {
+    pub fn new(writer: LedWriter, min_interval: Duration) -> Rc<Self> {
+        Rc::new(Self {
+            writer,
+            min_interval,
+            last_write: RefCell::new(None),
+            pending: RefCell::new(None),
+        })
+    }
+
+    /// Request that `brightness` be written, immediately if the minimum
+    /// interval has elapsed since the last write, otherwise deferred
+    /// (overwriting any previously-pending value) until the supervisor
+    /// timer flushes it. Returns whether the write went out immediately
+    /// (`true`) or was coalesced into `pending` (`false`), for
+    /// `--trace-state-machine` to report.
+    pub fn request(&self, brightness: u32) -> bool {
+        let ready = match *self.last_write.borrow() {
+            Some(last) => last.elapsed() >= self.min_interval,
+            None => true,
+        };
+        if ready {
+            self.write_now(brightness);
+        } else {
+            *self.pending.borrow_mut() = Some(brightness);
+        }
+        ready
+    }
+
+    fn write_now(&self, brightness: u32) {
+        self.writer.request(brightness);
+        *self.last_write.borrow_mut() = Some(Instant::now());
+    }
+
+    fn flush_if_due(&self) {
+        let Some(pending) = *self.pending.borrow() else {
+            return;
+        };
+        let ready = match *self.last_write.borrow() {
+            Some(last) => last.elapsed() >= self.min_interval,
+            None => true,
+        };
+        if ready {
+            *self.pending.borrow_mut() = None;
+            self.write_now(pending);
+        }
+    }
+}
+
+/// Start a supervisor timer that periodically flushes any pending
+/// rate-limited write once its deadline has passed. Ticks at a quarter
+/// of `min_interval` (clamped to a sane minimum) since the writer itself
+/// can't re-arm a one-shot timer pointed at its own non-`'static`
+/// `TimerSource` from within a `'static` callback.
+pub fn start_flusher(loop_: &LoopRef, writer: Rc<RateLimitedWriter>, min_interval: Duration) {
+    let tick = (min_interval / 4).max(Duration::from_millis(10));
+    let timer = loop_.add_timer(move |_expirations| {
+        writer.flush_if_due();
+    });
+    let _ = timer.update_timer(Some(tick), Some(tick));
+    std::mem::forget(timer);
+}
+
+// Exercises `RateLimitedWriter::request`'s coalescing decision itself
+// (its return value and `pending` field), not the backend write it
+// triggers — that needs a real `LedWriter` worker thread, for which
+// `--features dummy`'s `DummyBackend` is the documented no-real-device
+// stand-in (see `Cargo.toml`'s `dummy` feature).
+#[cfg(all(test, feature = "dummy"))]
+mod tests {
+    use super::*;
+    use crate::led::DummyBackend;
+
+    fn writer() -> LedWriter {
+        LedWriter::spawn(Box::new(DummyBackend::new(255)), false, false, None, None)
+    }
+
+    #[test]
+    fn first_request_always_applies_immediately() {
+        let limiter = RateLimitedWriter::new(writer(), Duration::from_secs(3600));
+        assert!(limiter.request(10));
+    }
+
+    #[test]
+    fn a_second_request_within_the_interval_is_coalesced() {
+        let limiter = RateLimitedWriter::new(writer(), Duration::from_secs(3600));
+        assert!(limiter.request(10));
+        assert!(!limiter.request(20));
+    }
+
+    #[test]
+    fn repeated_coalesced_requests_keep_only_the_latest_pending_value() {
+        let limiter = RateLimitedWriter::new(writer(), Duration::from_secs(3600));
+        assert!(limiter.request(10));
+        assert!(!limiter.request(20));
+        assert!(!limiter.request(30));
+        assert_eq!(*limiter.pending.borrow(), Some(30));
+    }
+
+    #[test]
+    fn a_zero_interval_never_coalesces() {
+        let limiter = RateLimitedWriter::new(writer(), Duration::ZERO);
+        assert!(limiter.request(10));
+        assert!(limiter.request(20));
+    }
+}
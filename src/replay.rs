@@ -0,0 +1,185 @@
+//! Offline replay of a recorded `pw-dump` JSON snapshot, for `--replay`.
+//! Runs the same identification/brightness logic as `main.rs`'s live
+//! registry handler against node props pulled out of the dump instead of
+//! a real PipeWire connection, so a user's `pw-dump > snapshot.json` can
+//! be handed to the maintainer (or run locally) without needing to
+//! reproduce the hardware/session live.
+//!
+//! [`run_states`] (`--replay-states`) is the lighter-weight, sequential
+//! sibling: rather than one static snapshot, it feeds a line-per-event
+//! script through the same decision path one step at a time, so flapping,
+//! removal, and state-before-props orderings can be scripted and
+//! reproduced deterministically — the things a single `pw-dump` snapshot
+//! can't represent, since it's only ever one point in time.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::rules::{self, CameraState};
+
+/// A `pw-dump` node's `info.state` string, mapped onto [`CameraState`].
+/// Separate from `rules::camera_state_from_node_state` since that maps
+/// `pipewire::node::NodeState`, not this JSON string representation.
+fn camera_state_from_dump_str(state: &str) -> CameraState {
+    match state {
+        "running" => CameraState::Active,
+        "idle" | "suspended" => CameraState::Inactive,
+        "creating" => CameraState::Unknown,
+        _ => CameraState::Error,
+    }
+}
+
+/// Parse `path` as `pw-dump` JSON, run identification + the brightness
+/// mapping against every `PipeWire:Interface:Node` entry it contains, and
+/// print a per-node report. Never touches PipeWire or D-Bus.
+pub fn run(path: &Path, cfg: &Config, any_camera: bool) -> anyhow::Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("failed to read {:?}: {}", path, err))?;
+    let dump: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|err| anyhow::anyhow!("failed to parse {:?} as JSON: {}", path, err))?;
+    let nodes = dump
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("expected a top-level JSON array, as pw-dump produces"))?;
+
+    let mut aggregate_brightness = None;
+    let mut matched_any = false;
+
+    for node in nodes {
+        if node.get("type").and_then(|v| v.as_str()) != Some("PipeWire:Interface:Node") {
+            continue;
+        }
+        let id = node.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+        let info = match node.get("info") {
+            Some(info) => info,
+            None => continue,
+        };
+        let props: HashMap<&str, &str> = info
+            .get("props")
+            .and_then(|v| v.as_object())
+            .map(|map| {
+                map.iter()
+                    .filter_map(|(k, v)| Some((k.as_str(), v.as_str()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let is_tracked = if any_camera {
+            rules::matches_any_camera(&props)
+        } else if cfg.match_threshold.is_some() {
+            rules::matches_camera_scored(&props, cfg)
+        } else {
+            rules::matches_camera(&props, cfg)
+        };
+        if !is_tracked {
+            continue;
+        }
+        matched_any = true;
+
+        let state = info
+            .get("state")
+            .and_then(|v| v.as_str())
+            .map(camera_state_from_dump_str)
+            .unwrap_or(CameraState::Unknown);
+        let brightness = cfg.brightness_for(state);
+        aggregate_brightness = Some(aggregate_brightness.unwrap_or(0).max(brightness));
+
+        println!(
+            "id:{} node.name:{:?} state:{:?} -> brightness:{}",
+            id,
+            props.get("node.name").unwrap_or(&""),
+            state,
+            brightness
+        );
+    }
+
+    if !matched_any {
+        println!("no matching camera node found in {:?}", path);
+    }
+    println!(
+        "resulting led brightness: {}",
+        aggregate_brightness.unwrap_or(0)
+    );
+    Ok(())
+}
+
+/// `<id> <state> [key=value ...]` per line, for `--replay-states`. `state`
+/// is one of `running`/`idle`/`suspended`/`creating`/`error`/`removed`
+/// (the first five map through [`camera_state_from_dump_str`], `removed`
+/// drops the node entirely, mirroring `global_remove` in `main.rs`).
+/// `key=value` pairs are accumulated per id across lines rather than
+/// replaced, so a line can arrive with a state but no props yet (props
+/// following in a later line) without losing the props already known for
+/// that id — the "state-before-props" ordering this mode exists to
+/// reproduce. Blank lines and lines starting with `#` are ignored.
+pub fn run_states(path: &Path, cfg: &Config, any_camera: bool) -> anyhow::Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("failed to read {:?}: {}", path, err))?;
+
+    let mut props_by_id: HashMap<u32, HashMap<String, String>> = HashMap::new();
+    let mut camera_states: HashMap<u32, CameraState> = HashMap::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let id: u32 = match fields.next().and_then(|s| s.parse().ok()) {
+            Some(id) => id,
+            None => {
+                eprintln!("--replay-states: {:?} line {}: missing/invalid id", path, line_number + 1);
+                std::process::exit(1);
+            }
+        };
+        let state_str = fields.next().unwrap_or_else(|| {
+            eprintln!("--replay-states: {:?} line {}: missing state", path, line_number + 1);
+            std::process::exit(1);
+        });
+
+        if state_str == "removed" {
+            props_by_id.remove(&id);
+            camera_states.remove(&id);
+            println!("id:{} removed", id);
+        } else {
+            let props = props_by_id.entry(id).or_default();
+            for field in fields {
+                if let Some((key, value)) = field.split_once('=') {
+                    props.insert(key.to_string(), value.to_string());
+                }
+            }
+            let props_lookup: HashMap<&str, &str> =
+                props.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+            let is_tracked = if any_camera {
+                rules::matches_any_camera(&props_lookup)
+            } else if cfg.match_threshold.is_some() {
+                rules::matches_camera_scored(&props_lookup, cfg)
+            } else {
+                rules::matches_camera(&props_lookup, cfg)
+            };
+            if is_tracked {
+                let state = camera_state_from_dump_str(state_str);
+                camera_states.insert(id, state);
+                println!(
+                    "id:{} node.name:{:?} state:{:?}",
+                    id,
+                    props.get("node.name").map(String::as_str).unwrap_or(""),
+                    state
+                );
+            } else {
+                camera_states.remove(&id);
+                println!("id:{} not tracked (match failed)", id);
+            }
+        }
+
+        let led_brightness = camera_states
+            .values()
+            .map(|state| cfg.brightness_for(*state))
+            .max()
+            .unwrap_or(0);
+        println!("  -> led brightness: {}", led_brightness);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! `--session-scope foreground|any|seat`: on a fast-user-switching
+//! machine, decide whether the LED reacts to any logged-in user's camera,
+//! or only to whichever session is actually in the foreground.
+//!
+//! PipeWire's `pipewire.sec.uid` (see `rules::is_other_users_node`) is the
+//! finest-grained identity a node's props expose — there's no
+//! `pipewire.sec.session`/pid, so this can only correlate a node to *a*
+//! logind session owned by that uid, not to the exact session that opened
+//! the camera. On the common fast-user-switching setup (one session per
+//! uid) that's exact; on a box where the same user holds multiple
+//! concurrent sessions (e.g. one on the console plus one over SSH), a
+//! node is treated as in-scope as soon as any one of that uid's sessions
+//! is in scope, which can't be tightened further without a property
+//! PipeWire doesn't stamp.
+//!
+//! Watched the same way `sessionlock::watch` watches `LockedHint`: a
+//! dedicated thread owns the blocking zbus connection, this time polling
+//! logind's `ListSessions` (there's no single signal for "the foreground
+//! session changed" to subscribe to instead - it's a property on each
+//! `Session` object) and publishing the current in-scope uid set into a
+//! `Mutex`, since unlike `sessionlock`'s single bool this is a whole set
+//! and the blocking-iterator trick `sessionlock`/`suspend` use only fits
+//! a single always-available signal stream.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use zbus::blocking::Connection;
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+/// How often to re-poll logind for the current foreground/seat session
+/// set. Fast user switching is a human-paced action; this doesn't need
+/// to be any snappier than `als::POLL_INTERVAL`.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionScope {
+    /// No filtering by session at all - the prior, and still default,
+    /// behavior: any logged-in user's camera drives the LED.
+    Any,
+    /// Only a uid whose session is the active one on *some* seat.
+    Foreground,
+    /// Only a uid whose session is the active one on this process's own
+    /// seat specifically, ignoring any other seat (e.g. a headless
+    /// second seat, or a remote session with no seat at all).
+    Seat,
+}
+
+pub fn parse(value: &str) -> Result<SessionScope, String> {
+    match value {
+        "foreground" => Ok(SessionScope::Foreground),
+        "any" => Ok(SessionScope::Any),
+        "seat" => Ok(SessionScope::Seat),
+        other => Err(format!(
+            "--session-scope: unknown value {:?}, expected foreground/any/seat",
+            other
+        )),
+    }
+}
+
+/// One row of logind's `ListSessions` reply.
+struct SessionRow {
+    path: OwnedObjectPath,
+    uid: u32,
+    seat: String,
+}
+
+fn list_sessions(connection: &Connection) -> anyhow::Result<Vec<SessionRow>> {
+    let reply = connection.call_method(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1",
+        Some("org.freedesktop.login1.Manager"),
+        "ListSessions",
+        &(),
+    )?;
+    let rows: Vec<(String, u32, String, String, OwnedObjectPath)> = reply.body().deserialize()?;
+    Ok(rows
+        .into_iter()
+        .map(|(_id, uid, _user, seat, path)| SessionRow { path, uid, seat })
+        .collect())
+}
+
+fn session_active(connection: &Connection, path: &OwnedObjectPath) -> anyhow::Result<bool> {
+    let reply = connection.call_method(
+        Some("org.freedesktop.login1"),
+        path,
+        Some("org.freedesktop.DBus.Properties"),
+        "Get",
+        &("org.freedesktop.login1.Session", "Active"),
+    )?;
+    let value: Value = reply.body().deserialize()?;
+    bool::try_from(value).map_err(anyhow::Error::from)
+}
+
+/// Our own session's seat id, for `SessionScope::Seat`. `None` if we're
+/// not attached to a seat at all (e.g. a headless/remote session), in
+/// which case `Seat` scope excludes everything - there's no seat for
+/// anything to be "the active session" on.
+fn own_seat(connection: &Connection) -> Option<String> {
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.login1"),
+            "/org/freedesktop/login1/session/auto",
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &("org.freedesktop.login1.Session", "Seat"),
+        )
+        .ok()?;
+    let value: Value = reply.body().deserialize().ok()?;
+    let (seat_id, _path): (String, OwnedObjectPath) = value.try_into().ok()?;
+    if seat_id.is_empty() {
+        None
+    } else {
+        Some(seat_id)
+    }
+}
+
+fn poll_once(connection: &Connection, scope: SessionScope, own_seat: Option<&str>) -> HashSet<u32> {
+    let rows = match list_sessions(connection) {
+        Ok(rows) => rows,
+        Err(err) => {
+            log::warn!("--session-scope: ListSessions failed: {:?}", err);
+            return HashSet::new();
+        }
+    };
+    rows.into_iter()
+        .filter(|row| match scope {
+            SessionScope::Any => true,
+            SessionScope::Foreground => true,
+            SessionScope::Seat => own_seat.is_some_and(|seat| seat == row.seat),
+        })
+        .filter(|row| session_active(connection, &row.path).unwrap_or(false))
+        .map(|row| row.uid)
+        .collect()
+}
+
+/// Start polling logind for the current in-scope uid set; `scope` must
+/// not be [`SessionScope::Any`] (the caller skips starting this watcher
+/// entirely in that case, same as `--only-when-unlocked`'s `sessionlock`
+/// only being started when the flag is on).
+pub fn watch(scope: SessionScope) -> anyhow::Result<Arc<Mutex<HashSet<u32>>>> {
+    let connection = Connection::system()?;
+    let seat = own_seat(&connection);
+    let in_scope = Arc::new(Mutex::new(poll_once(&connection, scope, seat.as_deref())));
+    let shared = in_scope.clone();
+
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+        let uids = poll_once(&connection, scope, seat.as_deref());
+        *shared.lock().unwrap() = uids;
+    });
+
+    Ok(in_scope)
+}
@@ -0,0 +1,96 @@
+use anyhow::Context;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::thread;
+
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::Value;
+
+/// Read logind's `LockedHint` property once, synchronously, for the
+/// initial value `watch` starts its flag at — the `PropertiesChanged`
+/// signal only reports *changes*, not the value at subscription time, so
+/// a session that's already locked when this daemon starts needs this
+/// one explicit read to notice.
+fn read_locked_hint(connection: &Connection) -> anyhow::Result<bool> {
+    let reply = connection.call_method(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1/session/auto",
+        Some("org.freedesktop.DBus.Properties"),
+        "Get",
+        &("org.freedesktop.login1.Session", "LockedHint"),
+    )?;
+    let value: Value = reply.body().deserialize()?;
+    bool::try_from(value).context("LockedHint property wasn't a bool")
+}
+
+/// Watches logind's session `LockedHint` property (via `PropertiesChanged`
+/// on `org.freedesktop.login1.Session`) on a dedicated thread, for
+/// `--only-when-unlocked` to suppress lighting the LED while the session
+/// is locked. Same shape as `suspend::watch`'s `PrepareForSleep` watcher,
+/// for the same reason: zbus's blocking signal iterator blocks for the
+/// life of the connection, which doesn't fit this daemon's non-blocking,
+/// callback-driven style around `pipewire::main_loop::MainLoop`.
+pub fn watch() -> anyhow::Result<Arc<AtomicBool>> {
+    let connection = Connection::system()?;
+    let initial = read_locked_hint(&connection).unwrap_or_else(|err| {
+        log::warn!(
+            "--only-when-unlocked: failed to read initial LockedHint, assuming unlocked: {:?}",
+            err
+        );
+        false
+    });
+    let locked = Arc::new(AtomicBool::new(initial));
+    let flag = locked.clone();
+
+    thread::spawn(move || {
+        let proxy = match Proxy::new(
+            &connection,
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1/session/auto",
+            "org.freedesktop.DBus.Properties",
+        ) {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                log::error!("--only-when-unlocked: failed to open Properties proxy: {:?}", err);
+                return;
+            }
+        };
+        let signals = match proxy.receive_signal("PropertiesChanged") {
+            Ok(signals) => signals,
+            Err(err) => {
+                log::error!(
+                    "--only-when-unlocked: failed to subscribe to PropertiesChanged: {:?}",
+                    err
+                );
+                return;
+            }
+        };
+        for signal in signals {
+            let body = signal.body();
+            match body.deserialize::<(String, std::collections::HashMap<String, Value>, Vec<String>)>() {
+                Ok((interface, changed, _invalidated)) => {
+                    if interface != "org.freedesktop.login1.Session" {
+                        continue;
+                    }
+                    if let Some(value) = changed.get("LockedHint") {
+                        match bool::try_from(value.clone()) {
+                            Ok(locked_hint) => {
+                                flag.store(locked_hint, std::sync::atomic::Ordering::SeqCst);
+                            }
+                            Err(err) => log::warn!(
+                                "--only-when-unlocked: LockedHint wasn't a bool: {:?}",
+                                err
+                            ),
+                        }
+                    }
+                }
+                Err(err) => log::warn!(
+                    "--only-when-unlocked: malformed PropertiesChanged signal: {:?}",
+                    err
+                ),
+            }
+        }
+    });
+
+    Ok(locked)
+}
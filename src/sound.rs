@@ -0,0 +1,37 @@
+//! Optional audible cues on camera state change, via `--sound-on`/
+//! `--sound-off`. Played by spawning `aplay` (ALSA's command-line
+//! player, present on essentially every Linux desktop/distro without an
+//! extra dependency on this crate's side) rather than linking against
+//! `libcanberra` or talking to a sound-theme D-Bus service, since this
+//! crate has no audio-library dependency at all yet and a configured
+//! file path is simplest to hand straight to a player binary.
+
+use std::process::{Command, Stdio};
+
+/// Play `path` without blocking the caller: the child is spawned and
+/// waited on from a dedicated thread, the same "kick off blocking I/O on
+/// its own thread rather than stall the main loop" shape
+/// `writer::LedWriter` uses for LED writes, so a slow or hanging player
+/// can't delay camera-state handling.
+pub fn play_async(path: &str) {
+    let path = path.to_string();
+    match Command::new("aplay")
+        .arg("-q")
+        .arg(&path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(mut child) => {
+            std::thread::spawn(move || {
+                if let Err(err) = child.wait() {
+                    log::warn!("sound cue {:?}: aplay failed: {:?}", path, err);
+                }
+            });
+        }
+        Err(err) => {
+            log::warn!("sound cue {:?}: failed to spawn aplay: {:?}", path, err);
+        }
+    }
+}
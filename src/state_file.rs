@@ -0,0 +1,79 @@
+//! Persisting the identifying props of the matched front camera across
+//! restarts, for `--state-file`. Pre-seeds `resolve_identification` on the
+//! next run so the daemon doesn't spend the first few PipeWire events
+//! re-discovering a camera it already knew about.
+
+use std::fs;
+
+/// The subset of a matched node's identifying props worth persisting —
+/// enough for `resolve_identification` to pre-seed `Config` with, not a
+/// full prop dump (`--dump-node` already does that, to stdout rather than
+/// a file). Any field may be absent if the matched node didn't report that
+/// prop.
+///
+/// File format: a single flat JSON object with the same field names as
+/// this struct, e.g.
+/// `{"camera_product_name": "...", "front_location": "front", "pipeline_handler": null}`.
+/// Written via `serde_json::json!` and read back field-by-field out of a
+/// `serde_json::Value` rather than a derived struct — this crate depends
+/// on `serde_json` but not `serde` itself (see `status()` in `main.rs` for
+/// the same loose-`Value` style), and three optional string fields don't
+/// justify adding it just for a derive.
+#[derive(Debug, Default, Clone)]
+pub struct PersistedIdentity {
+    pub camera_product_name: Option<String>,
+    pub front_location: Option<String>,
+    pub pipeline_handler: Option<String>,
+}
+
+/// Load a previously-saved identity from `path`. A missing file, an
+/// unreadable file, and malformed JSON are all treated the same way: log
+/// and return `None`, falling back to normal discovery — a stale or
+/// corrupt state file must never prevent the daemon from starting.
+pub fn load(path: &str) -> Option<PersistedIdentity> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            log::debug!(
+                "--state-file: couldn't read {:?}, starting without a pre-seeded identity: {:?}",
+                path,
+                err
+            );
+            return None;
+        }
+    };
+    let value: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(err) => {
+            log::warn!("--state-file: {:?} isn't valid JSON, ignoring it: {:?}", path, err);
+            return None;
+        }
+    };
+    let string_field = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+    Some(PersistedIdentity {
+        camera_product_name: string_field("camera_product_name"),
+        front_location: string_field("front_location"),
+        pipeline_handler: string_field("pipeline_handler"),
+    })
+}
+
+/// Save `identity` to `path`, overwriting whatever was there. Errors are
+/// logged, not propagated — persisting is a best-effort convenience, not
+/// something a failure should take the daemon down over.
+pub fn save(path: &str, identity: &PersistedIdentity) {
+    let value = serde_json::json!({
+        "camera_product_name": identity.camera_product_name,
+        "front_location": identity.front_location,
+        "pipeline_handler": identity.pipeline_handler,
+    });
+    let content = match serde_json::to_string_pretty(&value) {
+        Ok(content) => content,
+        Err(err) => {
+            log::warn!("--state-file: failed to serialize identity, not writing {:?}: {:?}", path, err);
+            return;
+        }
+    };
+    if let Err(err) = fs::write(path, content) {
+        log::warn!("--state-file: failed to write {:?}: {:?}", path, err);
+    }
+}
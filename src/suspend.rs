@@ -0,0 +1,52 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::thread;
+
+use zbus::blocking::{Connection, Proxy};
+
+/// Watches logind's `PrepareForSleep` signal on a dedicated thread and
+/// reports whether the system is currently mid-suspend/resume, for
+/// `--smooth-suspend` to suppress LED writes across that window rather
+/// than flicking the LED off then on as PipeWire re-enumerates.
+///
+/// This runs on its own thread rather than inside the PipeWire main loop
+/// because zbus's blocking signal iterator blocks for the life of the
+/// connection, which doesn't fit the non-blocking, callback-driven style
+/// the rest of this daemon uses around `pipewire::main_loop::MainLoop`.
+pub fn watch() -> anyhow::Result<Arc<AtomicBool>> {
+    let suspended = Arc::new(AtomicBool::new(false));
+    let flag = suspended.clone();
+    let connection = Connection::system()?;
+
+    thread::spawn(move || {
+        let proxy = match Proxy::new(
+            &connection,
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+        ) {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                log::error!("--smooth-suspend: failed to open login1.Manager proxy: {:?}", err);
+                return;
+            }
+        };
+        let signals = match proxy.receive_signal("PrepareForSleep") {
+            Ok(signals) => signals,
+            Err(err) => {
+                log::error!("--smooth-suspend: failed to subscribe to PrepareForSleep: {:?}", err);
+                return;
+            }
+        };
+        for signal in signals {
+            match signal.body().deserialize::<bool>() {
+                Ok(going_to_sleep) => {
+                    flag.store(going_to_sleep, std::sync::atomic::Ordering::SeqCst);
+                }
+                Err(err) => log::warn!("--smooth-suspend: malformed PrepareForSleep signal: {:?}", err),
+            }
+        }
+    });
+
+    Ok(suspended)
+}
@@ -0,0 +1,65 @@
+//! Tracks in-flight `core.sync()` requests for the registry-readiness
+//! signal in `monitor()`'s startup path, so `done` firing for the wrong
+//! `seq` (out-of-order, or a second sync issued concurrently) can't be
+//! mistaken for "the sync we're waiting on completed".
+//!
+//! Today `monitor()` only ever issues one sync (see its `initial_sync_seq`
+//! comment), so in practice this set never holds more than one entry at a
+//! time - but `done` events aren't guaranteed to arrive in request order,
+//! and nothing stops a future addition from issuing a second sync (e.g. a
+//! per-node round-trip) while the initial one is still outstanding. Using
+//! a set rather than a single `AsyncSeq` comparison means readiness
+//! doesn't need to be rewritten if that happens; it already only fires
+//! once every outstanding sync has been accounted for.
+
+use pipewire::spa::utils::result::AsyncSeq;
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// How long to wait for every pending sync to complete before giving up
+/// and firing readiness anyway - a core that never sends `done` (e.g. a
+/// PipeWire version with a protocol bug) shouldn't block startup forever.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `AsyncSeq` has no `Hash` impl (see `libspa`'s `utils::result`), so this
+/// is a small `Vec` rather than a `HashSet` - the number of concurrently
+/// outstanding syncs is always tiny, making the linear scan irrelevant.
+pub struct PendingSyncs {
+    pending: RefCell<Vec<AsyncSeq>>,
+}
+
+impl PendingSyncs {
+    pub fn new() -> Self {
+        Self {
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Register a sync we're now waiting on.
+    pub fn push(&self, seq: AsyncSeq) {
+        self.pending.borrow_mut().push(seq);
+    }
+
+    /// Record that `seq` completed, if it's one we're actually waiting
+    /// on. Returns whether it matched - a `done` whose `seq` isn't in
+    /// this set belongs to some other sync entirely and should be
+    /// ignored rather than treated as progress.
+    pub fn mark_done(&self, seq: AsyncSeq) -> bool {
+        let mut pending = self.pending.borrow_mut();
+        let before = pending.len();
+        pending.retain(|&pending_seq| pending_seq != seq);
+        pending.len() < before
+    }
+
+    /// Whether every sync registered via `push` has since been matched
+    /// by `mark_done` - the actual readiness condition.
+    pub fn is_empty(&self) -> bool {
+        self.pending.borrow().is_empty()
+    }
+}
+
+impl Default for PendingSyncs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,127 @@
+//! Minimal `sd_listen_fds(3)`/`sd_notify(3)`-style systemd integration,
+//! implemented without a `libsystemd` dependency since both only need a
+//! couple of env vars and a `AF_UNIX` `SOCK_DGRAM` socket, nothing the C
+//! library does that `std` can't.
+//!
+//! `listen_fds` is consumed by `health::start()`, so `--health-socket`
+//! can be socket-activated (`Sockets=`/`ListenStream=` in the unit)
+//! instead of binding its own socket every startup. `notify_ready` is
+//! consumed by `monitor()`'s initial registry sync (see its
+//! `core.sync`/`done` handling).
+//!
+//! [`resolve_runtime_path`] handles the other half of systemd interop:
+//! where a relative path given to a socket/state-file flag (`--health-socket`,
+//! `--state-file`, `--force-state-file`, `--event-csv`) actually lands.
+//! Resolution order, matching `RuntimeDirectory=x13s-camera-led` in a unit
+//! file:
+//! 1. `$RUNTIME_DIRECTORY` (set by systemd when the unit has
+//!    `RuntimeDirectory=`), so the path lives in a directory systemd
+//!    creates with the right permissions and cleans up on stop — nothing
+//!    in this crate has to manage that lifecycle itself.
+//! 2. `/run/x13s-camera-led`, for non-systemd service managers that still
+//!    run as root with `/run` writable, best-effort (not created if
+//!    missing — see [`runtime_dir`]).
+//! 3. `$XDG_RUNTIME_DIR/x13s-camera-led`, for a non-root, non-systemd
+//!    session (e.g. a user service manager without `RuntimeDirectory=`
+//!    support, or running interactively for testing).
+//!
+//! An absolute path given to any of those flags is used exactly as given,
+//! bypassing this resolution entirely — this only affects a bare filename.
+
+use std::os::fd::RawFd;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::path::PathBuf;
+
+/// File descriptors handed to us by systemd via socket activation, in
+/// order starting at fd 3. Returns an empty vec when not socket-activated
+/// (e.g. `LISTEN_PID` doesn't match our pid), which is the no-op case for
+/// every caller that isn't run under systemd.
+pub fn listen_fds() -> Vec<RawFd> {
+    let Ok(pid) = std::env::var("LISTEN_PID") else {
+        return Vec::new();
+    };
+    if pid.parse::<u32>().ok() != Some(std::process::id()) {
+        return Vec::new();
+    }
+    let Ok(count) = std::env::var("LISTEN_FDS").and_then(|s| {
+        s.parse::<i32>().map_err(|_| std::env::VarError::NotPresent)
+    }) else {
+        return Vec::new();
+    };
+    (0..count).map(|offset| 3 + offset as RawFd).collect()
+}
+
+/// Tell systemd (if `$NOTIFY_SOCKET` is set, i.e. we were started with
+/// `Type=notify`) that startup has finished. A no-op everywhere else —
+/// including under every other service manager, and when run interactively
+/// — so it's always safe to call unconditionally. Errors (socket doesn't
+/// exist, send fails) are logged but never fatal: readiness notification
+/// is a nice-to-have for faster-starting units, not something the daemon's
+/// own correctness depends on.
+pub fn notify_ready() {
+    let Ok(path) = std::env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+    // A leading `@` spells an abstract socket address (one not backed by a
+    // filesystem path); see `sd_notify(3)`.
+    let addr = if let Some(name) = path.strip_prefix('@') {
+        SocketAddr::from_abstract_name(name.as_bytes())
+    } else {
+        SocketAddr::from_pathname(&path)
+    };
+    let addr = match addr {
+        Ok(addr) => addr,
+        Err(err) => {
+            log::warn!("sd_notify: bad $NOTIFY_SOCKET {:?}: {:?}", path, err);
+            return;
+        }
+    };
+    let socket = match UnixDatagram::unbound() {
+        Ok(socket) => socket,
+        Err(err) => {
+            log::warn!("sd_notify: failed to create socket: {:?}", err);
+            return;
+        }
+    };
+    if let Err(err) = socket.send_to_addr(b"READY=1", &addr) {
+        log::warn!("sd_notify: failed to notify readiness: {:?}", err);
+    }
+}
+
+/// The runtime directory a relative socket/state-file path should resolve
+/// under, per the fallback chain documented in this module's doc comment.
+/// Doesn't create the directory itself (systemd already creates
+/// `$RUNTIME_DIRECTORY`; `/run/x13s-camera-led` and
+/// `$XDG_RUNTIME_DIR/x13s-camera-led` are used as-is, best-effort, and a
+/// later file open failing there is reported the same way any other
+/// `--state-file`/`--health-socket` open failure is).
+pub fn runtime_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("RUNTIME_DIRECTORY") {
+        return PathBuf::from(dir);
+    }
+    let run_dir = PathBuf::from("/run/x13s-camera-led");
+    if run_dir.is_dir() {
+        return run_dir;
+    }
+    if let Ok(xdg) = std::env::var("XDG_RUNTIME_DIR") {
+        return PathBuf::from(xdg).join("x13s-camera-led");
+    }
+    // Neither exists; return it anyway so the eventual file/socket open
+    // fails with a clear "no such file or directory" rather than this
+    // function silently picking something unexpected.
+    run_dir
+}
+
+/// Resolve `path` against [`runtime_dir`] when it's relative (a bare
+/// filename like `health.sock`), or return it unchanged when it's already
+/// absolute — an explicit absolute path always means exactly that path,
+/// same as before this resolution existed.
+pub fn resolve_runtime_path(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        path
+    } else {
+        runtime_dir().join(path)
+    }
+}
@@ -0,0 +1,43 @@
+use std::io::Write;
+
+/// Minimal live status display for `--watch`, redrawn in place with ANSI
+/// escapes. Intentionally not a full TUI (no input handling, no extra
+/// dependency) — this is a diagnostic aid, not an interactive app.
+pub struct Watch {
+    last_lines: usize,
+}
+
+impl Watch {
+    pub fn new() -> Self {
+        Self { last_lines: 0 }
+    }
+
+    /// Redraw the status block in place, erasing the previous one first.
+    pub fn render(&mut self, camera_states: &[(u32, bool)], led_brightness: u32) {
+        let mut out = std::io::stdout();
+
+        for _ in 0..self.last_lines {
+            let _ = write!(out, "\x1b[1A\x1b[2K");
+        }
+
+        let mut lines = Vec::new();
+        lines.push(format!("led brightness: {}", led_brightness));
+        if camera_states.is_empty() {
+            lines.push("no camera identified yet".to_string());
+        } else {
+            for (id, running) in camera_states {
+                lines.push(format!(
+                    "  camera id:{} state:{}",
+                    id,
+                    if *running { "running" } else { "idle" }
+                ));
+            }
+        }
+
+        for line in &lines {
+            let _ = writeln!(out, "{}", line);
+        }
+        self.last_lines = lines.len();
+        let _ = out.flush();
+    }
+}
@@ -0,0 +1,234 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::led::LedBackend;
+
+/// Longest single backend write seen so far, logged at debug whenever a
+/// new high-water mark is hit. No metrics feature/exporter exists in
+/// this tree yet (see `Cargo.toml`'s feature note) to expose this as an
+/// actual metric, so it's just a running max surfaced through the log.
+static MAX_WRITE_NANOS: AtomicU64 = AtomicU64::new(0);
+
+/// Total backend write failures seen so far, across every `LedWriter`
+/// (there's normally just one per LED, primary or aux). Surfaced to
+/// `monitor()`'s end-of-run shutdown report; see `MAX_WRITE_NANOS` for why
+/// this is a bare counter rather than a real metric.
+static ERROR_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Total backend write failures recorded so far. For the shutdown report
+/// (see `--check-session` for the equivalent startup-time diagnostic).
+pub fn error_count() -> u64 {
+    ERROR_COUNT.load(Ordering::Relaxed)
+}
+
+enum Msg {
+    /// `Instant` is when the caller queued this write (`request`'s call
+    /// time), stood in for "when the triggering camera state change was
+    /// received" since nothing but cheap clamp logic runs between the two
+    /// — used to measure end-to-end event-to-write latency, see
+    /// `--max-event-latency-warn`.
+    Write(u32, Instant),
+    WriteAndAck(u32, Instant, Sender<()>),
+    /// Swap the backend the worker thread writes through, then
+    /// immediately re-apply the given (current) brightness via the new
+    /// backend — the "clean handoff" a backend/device switch needs: the
+    /// old backend is simply dropped (no LED-specific teardown exists to
+    /// run), and the LED ends up reflecting the current state through
+    /// whichever backend is now in charge, rather than sitting on
+    /// whatever the old backend last wrote. Queued through the same
+    /// channel as writes, so it's ordered correctly relative to any
+    /// writes already queued ahead of it.
+    SwapBackend(Box<dyn LedBackend>, u32),
+}
+
+/// Runs all [`LedBackend`] I/O — D-Bus or sysfs, doesn't matter which —
+/// on a dedicated worker thread, so a slow backend write never blocks
+/// the PipeWire main loop. The loop only ever queues desired values
+/// through this handle; the worker thread owns the backend exclusively
+/// and processes writes in the order they were queued.
+///
+/// There's no `JoinHandle`-based shutdown here: several supervisor
+/// timers (`pwm`, `ratelimit`, `health`) intentionally leak their own
+/// `LedWriter` clone for the life of the process (see `pwm::start`'s doc
+/// comment for why), so the channel this thread reads from never actually closes
+/// and a real `.join()` would just hang. [`LedWriter::request_blocking`]
+/// is the shutdown handshake that actually matters: it guarantees the
+/// final write — and everything queued ahead of it — has been applied
+/// before the caller proceeds, which is what matters for the LED ending
+/// up in the right state on exit. The thread itself is simply abandoned
+/// when the process exits, same as the leaked timers are.
+#[derive(Clone)]
+pub struct LedWriter {
+    tx: Sender<Msg>,
+}
+
+impl LedWriter {
+    /// `persist_error_status` keeps the most recent write-error
+    /// notification on screen (non-expiring) instead of letting it
+    /// disappear on its own, and closes it as soon as a write succeeds —
+    /// see `--persist-error-status`. `verify_write` additionally reads
+    /// the LED's sysfs `brightness` back after every write and warns on a
+    /// mismatch — see `--verify-write`.
+    /// `max_event_latency_warn`: if set, log a warning whenever the time
+    /// from a `request()` call to that write actually completing exceeds
+    /// this, see `--max-event-latency-warn`. `None` (default) never
+    /// checks this at all — the repo's usual "off unless asked" shape for
+    /// a diagnostic with a per-write cost (an extra `Instant::now()` and
+    /// comparison) not every caller wants to pay for.
+    pub fn spawn(
+        backend: Box<dyn LedBackend>,
+        persist_error_status: bool,
+        verify_write: bool,
+        max_event_latency_warn: Option<Duration>,
+        notify_fallback: Option<crate::notify_fallback::NotifyFallback>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel::<Msg>();
+        thread::spawn(move || {
+            let mut backend = backend;
+            for msg in rx {
+                match msg {
+                    Msg::Write(brightness, queued_at) => {
+                        write(&*backend, brightness, persist_error_status, verify_write, notify_fallback);
+                        warn_on_slow_event(queued_at, max_event_latency_warn);
+                    }
+                    Msg::WriteAndAck(brightness, queued_at, ack) => {
+                        write(&*backend, brightness, persist_error_status, verify_write, notify_fallback);
+                        warn_on_slow_event(queued_at, max_event_latency_warn);
+                        let _ = ack.send(());
+                    }
+                    Msg::SwapBackend(new_backend, current_brightness) => {
+                        backend = new_backend;
+                        write(&*backend, current_brightness, persist_error_status, verify_write, notify_fallback);
+                    }
+                }
+            }
+        });
+        Self { tx }
+    }
+
+    /// Queue `brightness` to be written, without waiting for (or being
+    /// able to observe) the result. What the PipeWire callbacks use,
+    /// since they must never block on backend I/O.
+    pub fn request(&self, brightness: u32) {
+        let _ = self.tx.send(Msg::Write(brightness, Instant::now()));
+    }
+
+    /// Queue `brightness` and block until it — and everything queued
+    /// before it — has actually been written. For the one place we need
+    /// that guarantee: the final write on process exit. This is the
+    /// graceful-shutdown drain: it signals the worker, waits for it to
+    /// actually apply this (now-last) value, then returns — there's
+    /// nothing left to join afterwards that the caller needs to wait on.
+    pub fn request_blocking(&self, brightness: u32) {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        if self
+            .tx
+            .send(Msg::WriteAndAck(brightness, Instant::now(), ack_tx))
+            .is_ok()
+        {
+            let _ = ack_rx.recv();
+        }
+    }
+
+    /// Swap the backend this writer's worker thread writes through, then
+    /// immediately re-apply `current_brightness` via the new backend, for
+    /// a clean handoff when a backend/device change happens live (e.g. a
+    /// future `--led-device`/backend reload). Every existing clone of
+    /// this `LedWriter` keeps working unchanged afterwards, since the
+    /// swap happens inside the worker thread rather than by restarting
+    /// it — the channel this handle holds is never replaced.
+    ///
+    /// There's no signal (SIGHUP or otherwise) wired up anywhere in this
+    /// tree to actually trigger a reload yet, so nothing calls this today;
+    /// it exists so that whenever such a reload path is added, the
+    /// backend-swap half of it doesn't also need solving then.
+    pub fn swap_backend(&self, backend: Box<dyn LedBackend>, current_brightness: u32) {
+        let _ = self.tx.send(Msg::SwapBackend(backend, current_brightness));
+    }
+}
+
+/// See `LedWriter::spawn`'s `max_event_latency_warn`: logs once per write
+/// that took too long end-to-end, including queueing delay behind
+/// whatever was ahead of it on the channel, not just the backend I/O
+/// itself (`MAX_WRITE_NANOS`/the per-write debug log above already cover
+/// that half in isolation).
+fn warn_on_slow_event(queued_at: Instant, max_event_latency_warn: Option<Duration>) {
+    if let Some(threshold) = max_event_latency_warn {
+        let elapsed = queued_at.elapsed();
+        if elapsed > threshold {
+            log::warn!(
+                "--max-event-latency-warn: event-to-write latency {:?} exceeded {:?}",
+                elapsed,
+                threshold
+            );
+        }
+    }
+}
+
+fn write(
+    backend: &dyn LedBackend,
+    brightness: u32,
+    persist_error_status: bool,
+    verify_write: bool,
+    notify_fallback: Option<crate::notify_fallback::NotifyFallback>,
+) {
+    let started = Instant::now();
+    let result = crate::led::set_brightness_checked(backend, brightness);
+    let elapsed = started.elapsed();
+    let previous_max = MAX_WRITE_NANOS.fetch_max(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    if elapsed.as_nanos() as u64 > previous_max {
+        log::debug!("LED write took {:?} (new max)", elapsed);
+    } else {
+        log::debug!("LED write took {:?}", elapsed);
+    }
+
+    match result {
+        Ok(()) => {
+            if persist_error_status {
+                if let Err(err) = crate::close_notification(crate::ERROR_NOTIFICATION_ID) {
+                    log::error!("failed to close error notification: {:?}", err);
+                }
+            }
+            // Read the sysfs value back to catch something else (e.g. a
+            // kernel LED trigger re-armed by another process) silently
+            // overriding what we just wrote — the write itself reported
+            // success, but the LED may not actually reflect it.
+            if verify_write {
+                match crate::led::read_brightness(&backend.device_name()) {
+                    Ok(observed) if observed != brightness => {
+                        log::warn!(
+                            "--verify-write: LED {:?} reads {} after writing {}; something else \
+                             may be controlling it",
+                            backend.device_name(),
+                            observed,
+                            brightness
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        log::warn!("--verify-write: failed to read back brightness: {:?}", err);
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            ERROR_COUNT.fetch_add(1, Ordering::Relaxed);
+            log::error!("failed to set LED brightness: {:?}", err);
+            let summary = crate::i18n::messages().camera_state_changed_summary;
+            let message = format!("{:?}", err);
+            if let Err(err) = crate::notification(summary, &message) {
+                log::error!("failed to send notification: {:?}", err);
+                // `notification()` fails when the session bus itself is
+                // unreachable (no desktop session, e.g. headless), and also
+                // when the `Notify` call is rejected or times out; the
+                // fallback runs in every one of those cases - see
+                // `--notify-fallback`.
+                if let Some(fallback) = notify_fallback {
+                    crate::notify_fallback::send(fallback, summary, &message);
+                }
+            }
+        }
+    }
+}